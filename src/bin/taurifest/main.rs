@@ -0,0 +1,238 @@
+// taurifest/src/bin/taurifest/main.rs
+//
+// Утилита командной строки для проверки и отладки приложений Taurifest
+// без запуска окон. Собирается с фичей `cli`:
+//
+//     cargo install taurifest --features cli
+//     taurifest new my-app --template todo
+//     taurifest new my-app --template crud --taurifest-path ../taurifest
+//     taurifest validate app/
+//     taurifest run app/ addTodo --body '{"text": "Milk"}' --profile dev
+//     taurifest render app/ todoList --reads todos
+//     taurifest schema --output app/manifest.schema.json
+
+use std::path::PathBuf;
+use std::process::ExitCode;
+use std::sync::Arc;
+
+use clap::{Parser, Subcommand};
+use serde_json::{json, Value};
+
+use taurifest::core::errors::AppError;
+use taurifest::core::manifest::Manifest;
+use taurifest::{Engine, EventSink};
+
+mod scaffold;
+
+use scaffold::{TaurifestSource, Template};
+
+#[derive(Parser)]
+#[command(name = "taurifest", version, about = "Validate, run and render Taurifest apps without a window")]
+struct Cli {
+    /// Печатать логи движка (спаны роутов, шагов, коннекторов) в stderr.
+    #[arg(short, long, global = true)]
+    verbose: bool,
+
+    /// Профиль из секции `profiles` манифеста (`TAURIFEST_PROFILE` важнее).
+    #[arg(long, global = true)]
+    profile: Option<String>,
+
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Создать новый проект Tauri + Taurifest с приложением-примером.
+    New {
+        /// Папка проекта (ее имя станет именем пакета).
+        path: PathBuf,
+        #[arg(long, value_enum, default_value = "todo")]
+        template: Template,
+        /// Взять `taurifest` из локальной папки вместо crates.io.
+        #[arg(long, conflicts_with = "taurifest_git")]
+        taurifest_path: Option<PathBuf>,
+        /// Взять `taurifest` из git-репозитория вместо crates.io.
+        #[arg(long)]
+        taurifest_git: Option<String>,
+    },
+    /// Проверить манифест, ассеты и все выражения. Код возврата 1, если найдены проблемы.
+    Validate {
+        /// Папка приложения с манифестом (`manifest.json`, `.yaml` или `.toml`).
+        app: PathBuf,
+    },
+    /// Выполнить роут на реальных данных из `app/data` и напечатать итоговый `context` и HTML.
+    Run {
+        app: PathBuf,
+        route: String,
+        /// `body` роута в виде JSON.
+        #[arg(long, default_value = "{}")]
+        body: String,
+        /// Добавить в вывод трассировку шагов.
+        #[arg(long)]
+        trace: bool,
+    },
+    /// Отрисовать компонент на реальных данных и напечатать HTML.
+    Render {
+        app: PathBuf,
+        component: String,
+        /// Коннекторы, данные которых попадут в `data` (через запятую).
+        #[arg(long, value_delimiter = ',')]
+        reads: Vec<String>,
+        /// `body` для шаблона в виде JSON.
+        #[arg(long, default_value = "null")]
+        body: String,
+    },
+    /// Напечатать JSON Schema для `manifest.json` (для `"$schema"` в редакторе).
+    Schema {
+        /// Записать схему в файл вместо stdout.
+        #[arg(long)]
+        output: Option<PathBuf>,
+    },
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+    let cli = Cli::parse();
+
+    if cli.verbose {
+        tracing_subscriber::fmt()
+            .with_writer(std::io::stderr)
+            .with_max_level(tracing::Level::DEBUG)
+            .init();
+    }
+
+    let profile = cli.profile.as_deref();
+    let result = match cli.command {
+        Command::New { path, template, taurifest_path, taurifest_git } => {
+            let source = match (taurifest_path, taurifest_git) {
+                (Some(local), _) => TaurifestSource::Path(local),
+                (None, Some(url)) => TaurifestSource::Git(url),
+                (None, None) => TaurifestSource::Registry,
+            };
+            new(path, template, &source)
+        }
+        Command::Validate { app } => validate(app, profile),
+        Command::Run { app, route, body, trace } => run(app, profile, &route, &body, trace).await,
+        Command::Render { app, component, reads, body } => render(app, profile, &component, &reads, &body).await,
+        Command::Schema { output } => schema(output),
+    };
+
+    match result {
+        Ok(code) => code,
+        Err(error) => {
+            eprintln!("error: {}", error);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn new(path: PathBuf, template: Template, source: &TaurifestSource) -> Result<ExitCode, AppError> {
+    let created = scaffold::generate(&path, template, source)?;
+    for file in &created {
+        println!("created {}", file.display());
+    }
+    println!();
+    println!("Next steps:");
+    println!("  cd {}", path.display());
+    println!("  taurifest validate app");
+    println!("  cargo run");
+    Ok(ExitCode::SUCCESS)
+}
+
+fn validate(app: PathBuf, profile: Option<&str>) -> Result<ExitCode, AppError> {
+    // Коннекторы в памяти: проверка не должна создавать или трогать файлы данных.
+    let engine = Engine::load_in_memory(&app, profile)?;
+    let problems = engine.validate();
+
+    if problems.is_empty() {
+        println!("{}: OK", app.display());
+        return Ok(ExitCode::SUCCESS);
+    }
+
+    for problem in &problems {
+        println!("{}", problem);
+    }
+    println!("{} problem(s) found", problems.len());
+    Ok(ExitCode::FAILURE)
+}
+
+async fn run(app: PathBuf, profile: Option<&str>, route: &str, body: &str, trace: bool) -> Result<ExitCode, AppError> {
+    let body = parse_json("--body", body)?;
+    let engine = Engine::load(&app, profile)?.with_trace(trace);
+    engine.set_event_sink(Arc::new(StderrEventSink));
+
+    let result = engine.run_route(route, body).await?;
+
+    let html: serde_json::Map<String, Value> = result.rendered.into_iter()
+        .map(|rendered| (rendered.component, Value::String(rendered.html)))
+        .collect();
+    let mut output = json!({ "context": result.context, "html": html });
+    if let Some(trace) = result.trace {
+        output["trace"] = serde_json::to_value(trace).unwrap_or(Value::Null);
+    }
+
+    println!("{}", serde_json::to_string_pretty(&output).unwrap_or_default());
+    Ok(ExitCode::SUCCESS)
+}
+
+async fn render(app: PathBuf, profile: Option<&str>, component: &str, reads: &[String], body: &str) -> Result<ExitCode, AppError> {
+    let body = parse_json("--body", body)?;
+    let engine = Engine::load(&app, profile)?;
+    if !engine.manifest().components.contains_key(component) {
+        return Err(AppError::Config(format!("Component '{}' not found in manifest", component)));
+    }
+
+    let data = engine.read_connectors(reads, &format!("component '{}'", component)).await?;
+    let context = json!({
+        "data": data,
+        "body": body,
+        "user": engine.resolve_user().await?,
+        "globals": engine.manifest().globals,
+    });
+
+    println!("{}", engine.render(component, &context).await?);
+    Ok(ExitCode::SUCCESS)
+}
+
+fn schema(output: Option<PathBuf>) -> Result<ExitCode, AppError> {
+    let schema = serde_json::to_string_pretty(&Manifest::schema()).unwrap_or_default();
+    match output {
+        Some(path) => {
+            std::fs::write(&path, schema + "\n")
+                .map_err(|e| AppError::Config(format!("Failed to write {:?}: {}", path, e)))?;
+            println!("written {}", path.display());
+        }
+        None => println!("{}", schema),
+    }
+    Ok(ExitCode::SUCCESS)
+}
+
+fn parse_json(argument: &str, text: &str) -> Result<Value, AppError> {
+    serde_json::from_str(text).map_err(|e| AppError::Config(format!("{} is not valid JSON: {}", argument, e)))
+}
+
+/// Печатает события, которые роут отправил бы в окна, в stderr -
+/// так они не смешиваются с JSON-результатом в stdout.
+struct StderrEventSink;
+
+impl EventSink for StderrEventSink {
+    fn emit(&self, event: &str, payload: Value) -> Result<(), AppError> {
+        eprintln!("event '{}' -> all: {}", event, payload);
+        Ok(())
+    }
+
+    fn emit_to(&self, window_label: &str, event: &str, payload: Value) -> Result<(), AppError> {
+        eprintln!("event '{}' -> window '{}': {}", event, window_label, payload);
+        Ok(())
+    }
+
+    fn emit_except(&self, excluded_label: &str, event: &str, payload: Value) -> Result<(), AppError> {
+        eprintln!("event '{}' -> all except '{}': {}", event, excluded_label, payload);
+        Ok(())
+    }
+
+    fn window_labels(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,161 @@
+// taurifest/src/bin/taurifest/scaffold.rs
+//
+// Генератор нового проекта для `taurifest new`. Файлы шаблонов лежат рядом,
+// в `templates/`, и встраиваются в бинарник; в них подставляются только
+// `__APP_NAME__`, `__APP_TITLE__`, `__APP_ID__` и `__TAURIFEST_DEPENDENCY__`
+// (Mustache-теги компонентов не трогаем).
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use clap::ValueEnum;
+
+use taurifest::core::errors::AppError;
+use taurifest::core::manifest::Manifest;
+
+/// Какое приложение-пример сгенерировать.
+#[derive(Clone, Copy, Debug, ValueEnum)]
+pub enum Template {
+    /// Список задач: sqlite-коннектор, компонент со стилем, роуты с `update` и сокет.
+    Todo,
+    /// Заметки: создание, редактирование и удаление, несколько компонентов
+    /// в `update`, `action:run` и уведомления через `emit`.
+    Crud,
+}
+
+/// Откуда сгенерированный проект берет `taurifest`.
+#[derive(Clone, Debug)]
+pub enum TaurifestSource {
+    /// crates.io, ровно та версия, которой собран этот CLI: шаблоны
+    /// используют ее API, и более старая версия их не соберет.
+    Registry,
+    /// Локальная копия репозитория.
+    Path(PathBuf),
+    /// Git-репозиторий.
+    Git(String),
+}
+
+impl TaurifestSource {
+    /// Строка зависимости для `[dependencies]` в `Cargo.toml`.
+    fn dependency(&self) -> Result<String, AppError> {
+        // Строка JSON - допустимая базовая строка TOML.
+        let quote = |text: &str| serde_json::to_string(text).unwrap_or_default();
+        match self {
+            TaurifestSource::Registry => Ok(format!("taurifest = \"={}\"", env!("CARGO_PKG_VERSION"))),
+            TaurifestSource::Path(path) => {
+                // Относительный путь в `Cargo.toml` считался бы от папки нового проекта.
+                let path = fs::canonicalize(path)
+                    .map_err(|e| AppError::Config(format!("Cannot use {:?} as the taurifest path: {}", path, e)))?;
+                Ok(format!("taurifest = {{ path = {} }}", quote(&path.to_string_lossy())))
+            }
+            TaurifestSource::Git(url) => Ok(format!("taurifest = {{ git = {} }}", quote(url))),
+        }
+    }
+}
+
+/// Файлы, общие для всех шаблонов: Rust-часть и конфигурация Tauri.
+const COMMON_FILES: &[(&str, &str)] = &[
+    ("Cargo.toml", include_str!("templates/common/Cargo.toml.tmpl")),
+    ("build.rs", include_str!("templates/common/build.rs")),
+    ("src/main.rs", include_str!("templates/common/main.rs")),
+    ("tauri.conf.json", include_str!("templates/common/tauri.conf.json")),
+    ("capabilities/default.json", include_str!("templates/common/default.json")),
+    ("ui/index.html", include_str!("templates/common/index.html")),
+    (".gitignore", include_str!("templates/common/gitignore")),
+];
+
+const ICON: &[u8] = include_bytes!("templates/common/icon.png");
+
+const TODO_FILES: &[(&str, &str)] = &[
+    ("app/manifest.json", include_str!("templates/todo/manifest.json")),
+    ("app/components/layout.html", include_str!("templates/todo/layout.html")),
+    ("app/components/todo-list.html", include_str!("templates/todo/todo-list.html")),
+    ("app/components/todo-list.css", include_str!("templates/todo/todo-list.css")),
+    ("app/tests/add-todo.json", include_str!("templates/todo/add-todo.json")),
+];
+
+const CRUD_FILES: &[(&str, &str)] = &[
+    ("app/manifest.json", include_str!("templates/crud/manifest.json")),
+    ("app/components/layout.html", include_str!("templates/crud/layout.html")),
+    ("app/components/note-form.html", include_str!("templates/crud/note-form.html")),
+    ("app/components/note-list.html", include_str!("templates/crud/note-list.html")),
+    ("app/components/note-list.css", include_str!("templates/crud/note-list.css")),
+    ("app/components/note-summary.html", include_str!("templates/crud/note-summary.html")),
+    ("app/tests/notes.json", include_str!("templates/crud/notes.json")),
+];
+
+/// Создает проект в папке `target` (она должна не существовать или быть пустой).
+/// Имя пакета берется из имени папки. Возвращает пути созданных файлов.
+pub fn generate(target: &Path, template: Template, source: &TaurifestSource) -> Result<Vec<PathBuf>, AppError> {
+    if target.exists() && fs::read_dir(target).map(|mut entries| entries.next().is_some()).unwrap_or(true) {
+        return Err(AppError::Config(format!("Directory {:?} already exists and is not empty", target)));
+    }
+
+    let name = target.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .ok_or_else(|| AppError::Config(format!("Cannot take a project name from {:?}", target)))?;
+    let package = package_name(&name)?;
+    let title = title_case(&package);
+    let identifier = package.replace('_', "-");
+    let dependency = source.dependency()?;
+
+    let app_files = match template {
+        Template::Todo => TODO_FILES,
+        Template::Crud => CRUD_FILES,
+    };
+
+    let mut created = Vec::new();
+    for (relative, content) in COMMON_FILES.iter().chain(app_files) {
+        let content = content
+            .replace("__APP_NAME__", &package)
+            .replace("__APP_TITLE__", &title)
+            .replace("__APP_ID__", &identifier)
+            .replace("__TAURIFEST_DEPENDENCY__", &dependency);
+        created.push(write_file(target, relative, content.as_bytes())?);
+    }
+    created.push(write_file(target, "icons/icon.png", ICON)?);
+    // На схему ссылается `"$schema"` в манифесте шаблона.
+    let schema = serde_json::to_string_pretty(&Manifest::schema()).unwrap_or_default() + "\n";
+    created.push(write_file(target, "app/manifest.schema.json", schema.as_bytes())?);
+    // Папка для файлов sqlite-коннекторов.
+    created.push(write_file(target, "app/data/.gitkeep", b"")?);
+
+    Ok(created)
+}
+
+fn write_file(root: &Path, relative: &str, content: &[u8]) -> Result<PathBuf, AppError> {
+    let path = root.join(relative);
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| AppError::Config(format!("Failed to create directory {:?}: {}", parent, e)))?;
+    }
+    fs::write(&path, content).map_err(|e| AppError::Config(format!("Failed to write {:?}: {}", path, e)))?;
+    Ok(path)
+}
+
+/// Имя пакета Cargo: строчные латинские буквы, цифры, `-` и `_`, начинается с буквы.
+fn package_name(name: &str) -> Result<String, AppError> {
+    let package = name.to_lowercase();
+    let valid = package.starts_with(|c: char| c.is_ascii_lowercase())
+        && package.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_');
+    if valid {
+        Ok(package)
+    } else {
+        Err(AppError::Config(format!(
+            "'{}' is not a valid project name: use latin letters, digits, '-' and '_', starting with a letter",
+            name
+        )))
+    }
+}
+
+/// `my-notes` -> `My Notes`.
+fn title_case(package: &str) -> String {
+    package.split(['-', '_'])
+        .filter(|word| !word.is_empty())
+        .map(|word| {
+            let mut chars = word.chars();
+            chars.next().map(|first| first.to_ascii_uppercase().to_string() + chars.as_str()).unwrap_or_default()
+        })
+        .collect::<Vec<_>>()
+        .join(" ")
+}
@@ -0,0 +1,11 @@
+// Точка входа приложения: интерфейс, данные и логика описаны в `app/manifest.json`.
+#![cfg_attr(not(debug_assertions), windows_subsystem = "windows")]
+
+fn main() {
+    taurifest::Builder::new("app")
+        // В режиме разработки изменения манифеста и компонентов применяются без перезапуска.
+        .hot_reload(cfg!(debug_assertions))
+        .build()
+        .run(tauri::generate_context!())
+        .expect("error while running tauri application");
+}
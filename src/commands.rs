@@ -1,94 +1,124 @@
 // taurifest/src/commands.rs
 
-use tauri::{AppHandle, State};
+use tauri::{State, Window};
 use serde_json::Value;
 
 use crate::AppState;
-use crate::core::context::Context;
 use crate::core::errors::AppError;
+use crate::core::manifest::UpdateConfig;
 
 /// Основная команда, которая выполняет `action`-роут из манифеста.
 /// Вызывается из JavaScript как `invoke('run_action', { name: '...', body: ... })`.
+///
+/// Если передать `patch: true`, в ответ помимо полного `html` будет добавлен
+/// список `patches` - минимальные изменения относительно HTML, который этот
+/// компонент получил в этом же окне в прошлый раз.
 #[tauri::command]
 pub async fn run_action(
     name: String,
     body: Value,
+    patch: Option<bool>,
     state: State<'_, AppState>,
-    app_handle: AppHandle,
+    window: Window,
 ) -> Result<Value, AppError> {
 
-    // --- Шаг 1: Найти нужный роут в манифесте по его имени ---
-    let route = state.manifest.routes.get(&name)
-        .ok_or_else(|| AppError::Config(format!("Route '{}' not found in manifest", name)))?;
+    // --- Шаги 1-6: Выполнение роута и рендер компонентов из `update` (см. `Engine`) ---
+    let result = state.run_route_from(&name, body, Some(window.label())).await?;
 
-    // --- Шаг 2: Прочитать все необходимые данные из коннекторов (`reads`) ---
-    let mut data_map = serde_json::Map::new();
-    for connector_name in &route.reads {
-        if let Some(connector) = state.connector_manager.get(connector_name) {
-            let connector_data = connector.read().await?; 
-            data_map.insert(connector_name.clone(), connector_data);
-        } else {
-            return Err(AppError::Config(format!("Connector '{}' in reads for action '{}' is not defined.", connector_name, name)));
-        }
-    }
-    
-    // --- Шаг 3: Создать начальный контекст выполнения ---
-    let initial_context = Context::new(Value::Object(data_map), body, Value::Null); // `user` пока `Null`
-
-    // --- Шаг 4: Запустить ActionEngine для выполнения `steps` ---
-    let final_context = state.action_engine.run(
-        &route.steps, 
-        initial_context, 
-        &state, 
-        &app_handle
-    ).await?;
-    
-    // --- Шаг 5: Сохранить измененные данные и отправить real-time события ---
-    if let Some(data_object) = final_context.data.as_object() {
-        for connector_name in &route.writes {
-            if let (Some(connector), Some(data_to_write)) = (
-                state.connector_manager.get(connector_name),
-                data_object.get(connector_name)
-            ) {
-                // Сначала асинхронно записываем новые данные в базу
-                connector.write(data_to_write).await?;
-                
-                // Затем, после успешной записи, уведомляем SocketManager.
-                // Он проверит, нужно ли отправлять событие по этому поводу.
-                state.socket_manager.notify_on_write(
-                    connector_name,
-                    &state,
-                    &app_handle
-                ).await?;
+    // --- Шаг 7: Подготовить и вернуть JSON-ответ для UI ---
+    let mut response_map = serde_json::Map::new();
+
+    if let Some(update) = &result.update {
+        let mut html_map = serde_json::Map::new();
+        let mut patches_map = serde_json::Map::new();
+        let mut targets_map = serde_json::Map::new();
+
+        for rendered in result.rendered {
+            // Запоминаем HTML в любом случае: после полной замены DOM окна совпадает с ним.
+            // Сравнивать документы имеет смысл, только если клиент просил патчи.
+            if patch == Some(true) {
+                if let Some(patches) = state.renderer.diff_with_last(window.label(), &rendered.component, &rendered.html) {
+                    patches_map.insert(rendered.component.clone(), serde_json::to_value(patches).unwrap_or(Value::Null));
+                }
+            } else {
+                state.renderer.remember(window.label(), &rendered.component, &rendered.html);
             }
+            if let Some(target) = rendered.target {
+                targets_map.insert(rendered.component.clone(), Value::String(target));
+            }
+
+            // Полный HTML отдаем всегда: это запасной вариант, если патчи применить не удалось.
+            html_map.insert(rendered.component, Value::String(rendered.html));
         }
-    }
 
-    // --- Шаг 6: Подготовить и вернуть JSON-ответ для UI ---
-    let mut response_map = serde_json::Map::new();
+        // Для одиночного `update` сохраняем прежний формат ответа: `html` - строка,
+        // а имя компонента передаем отдельно в `component`.
+        if let UpdateConfig::Single(component_to_update) = update {
+            response_map.insert("component".to_string(), Value::String(component_to_update.clone()));
+            if let Some(html) = html_map.remove(component_to_update) {
+                response_map.insert("html".to_string(), html);
+            }
+            if let Some(patches) = patches_map.remove(component_to_update) {
+                response_map.insert("patches".to_string(), patches);
+            }
+        } else {
+            response_map.insert("html".to_string(), Value::Object(html_map));
+            if !patches_map.is_empty() {
+                response_map.insert("patches".to_string(), Value::Object(patches_map));
+            }
+            if !targets_map.is_empty() {
+                response_map.insert("targets".to_string(), Value::Object(targets_map));
+            }
+        }
 
-    if let Some(component_to_update) = &route.update {
-        // Создаем контекст специально для рендеринга.
-        let render_context = serde_json::json!({
-            "data": final_context.data,
-            "user": final_context.user,
-            "globals": state.manifest.globals,
-        });
-        
-        // Вызываем рендерер, чтобы получить новый HTML для компонента.
-        let html = state.renderer.render_component(
-            &state.asset_loader,
-            component_to_update,
-            &render_context
-        ).await?;
-        
-        response_map.insert("html".to_string(), Value::String(html));
-        
         // TODO: Добавить сюда же стили (`styles`) и другую мета-информацию.
     }
     
+    // В режиме трассировки возвращаем записи о шагах вместе с ответом.
+    if let Some(trace) = &result.trace {
+        response_map.insert("trace".to_string(), serde_json::to_value(trace).unwrap_or(Value::Null));
+    }
+
     // TODO: Добавить обработку `redirect`.
 
     // Возвращаем финальный JSON-объект, который получит JavaScript в `.then()`.
     Ok(Value::Object(response_map))
-}
\ No newline at end of file
+}
+
+/// Рендерит компонент без выполнения роута - для первой отрисовки экрана.
+/// Вызывается из JavaScript как
+/// `invoke('render_component', { component: 'todoList', reads: ['todos'], body: {} })`.
+///
+/// Проходит тот же путь, что и `run_action`: те же проверки коннекторов из `reads`
+/// и то же определение пользователя. Возвращает `{ html: "..." }`.
+#[tauri::command]
+pub async fn render_component(
+    component: String,
+    reads: Option<Vec<String>>,
+    body: Option<Value>,
+    state: State<'_, AppState>,
+    window: Window,
+) -> Result<Value, AppError> {
+    let manifest = state.manifest();
+    if !manifest.components.contains_key(&component) {
+        return Err(AppError::Config(format!("Component '{}' not found in manifest", component)));
+    }
+
+    let data = state.read_connectors(reads.as_deref().unwrap_or_default(), &format!("component '{}'", component)).await?;
+    let user = state.resolve_user().await?;
+
+    let render_context = serde_json::json!({
+        "data": data,
+        "body": body.unwrap_or(Value::Null),
+        "user": user,
+        "globals": manifest.globals,
+    });
+
+    let html = state.render(&component, &render_context).await?;
+
+    // Запоминаем первый рендер, чтобы последующие `run_action` с `patch: true`
+    // могли сразу вернуть патчи.
+    state.renderer.remember(window.label(), &component, &html);
+
+    Ok(serde_json::json!({ "html": html }))
+}
@@ -1,19 +1,29 @@
 // taurifest/src/core/action_engine.rs - ИСПРАВЛЕННАЯ ВЕРСИЯ С BOX::PIN
 
+use std::time::Instant;
+
 use rhai::{Engine, Scope, Dynamic};
 use serde_json::Value;
-use tauri::AppHandle;
 use futures::future::BoxFuture; // <-- СНОВА НУЖЕН ЭТОТ ИМПОРТ
+use tracing::{Instrument, Span};
 
 use crate::core::context::Context;
 use crate::core::manifest::Step; // <-- Убрали ActionRunStep, он внутри Step
 use crate::core::errors::ActionError;
+use crate::core::events::emit_to_target;
+use crate::core::trace::{diff_values, StepTrace};
 use crate::AppState;
 
 pub struct ActionEngine {
     engine: Engine,
 }
 
+impl Default for ActionEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl ActionEngine {
     pub fn new() -> Self {
         let mut engine = Engine::new();
@@ -31,58 +41,89 @@ impl ActionEngine {
         steps: &'s [Step],
         mut context: Context,
         state: &'s AppState,
-        app_handle: &'s AppHandle,
     ) -> BoxFuture<'s, Result<Context, ActionError>> {
         // --- ИЗМЕНЕНИЕ: Оборачиваем всю логику в Box::pin ---
         Box::pin(async move {
-            let mut scope = Scope::new();
-            scope.push_constant("data", context.data.clone());
-            scope.push_constant("body", context.body.clone());
-            scope.push_constant("user", context.user.clone());
-            scope.push("context", context.temp.clone());
+            let mut scope = build_scope(&context, &state.manifest().globals);
 
             let mut steps_to_process = steps.iter().rev().collect::<Vec<_>>();
 
             while let Some(step) = steps_to_process.pop() {
-                if let Some(action_run_config) = &step.action_run {
-                    let sub_route_name = &action_run_config.name;
+                // В режиме трассировки запоминаем состояние до шага, чтобы потом
+                // записать, что именно он изменил.
+                let started = Instant::now();
+                let snapshot_before = context.trace.is_some().then(|| snapshot(&scope));
+                let mut entry = context.trace.is_some().then(|| trace_entry(step));
+                let span = step_span(step);
+
+                match step {
+                    Step::ActionRun(action_run_config) => {
+                        let sub_route_name = &action_run_config.name;
                     
-                    if let Some(sub_route) = state.manifest.routes.get(sub_route_name) {
-                        println!("[ActionEngine] Running sub-action: '{}'", sub_route_name);
-                        
-                        let sub_context = Context::new(
-                            context.data.clone(),
-                            context.body.clone(),
-                            context.user.clone()
-                        );
+                        let manifest = state.manifest();
+                        if let Some(sub_route) = manifest.routes.get(sub_route_name) {
+                            span.in_scope(|| tracing::debug!(sub_route = %sub_route_name, "Running sub-action"));
+
+                            // Под-роут должен увидеть `data` с изменениями предыдущих шагов.
+                            context.data = scope_value(&scope, "data");
+                            let mut sub_context = Context::new(
+                                context.data.clone(),
+                                context.body.clone(),
+                                context.user.clone()
+                            );
+                            sub_context.origin = context.origin.clone();
+                            sub_context.trace = context.trace.as_ref().map(|_| Vec::new());
                         
-                        // Рекурсивный вызов теперь внутри `Box::pin`, все легально
-                        let result_context = self.run(&sub_route.steps, sub_context, state, app_handle).await?;
+                            // Рекурсивный вызов теперь внутри `Box::pin`, все легально
+                            let result_context = self.run(&sub_route.steps, sub_context, state)
+                                .instrument(span.clone())
+                                .await?;
                         
-                        context.data = result_context.data;
-                        context.temp = result_context.temp;
+                            context.data = result_context.data;
+                            context.temp = result_context.temp;
+                            if let Some(entry) = entry.as_mut() {
+                                entry.steps = result_context.trace.unwrap_or_default();
+                            }
                         
-                        scope = Scope::new();
-                        scope.push_constant("data", context.data.clone());
-                        scope.push_constant("body", context.body.clone());
-                        scope.push_constant("user", context.user.clone());
-                        scope.push("context", context.temp.clone());
-                    } else {
-                        return Err(ActionError::InvalidSetPath(format!("Sub-action '{}' not found", sub_route_name)));
+                            scope = build_scope(&context, &manifest.globals);
+                        } else {
+                            return Err(ActionError::InvalidSetPath(format!("Sub-action '{}' not found", sub_route_name)));
+                        }
+                    }
+                    Step::Emit(emit_config) => {
+                        let _entered = span.enter();
+                        let payload = match &emit_config.payload {
+                            Some(expr) => {
+                                let result = self.engine.eval_with_scope::<Dynamic>(&mut scope, expr)
+                                    .map_err(|e| ActionError::Rhai(e.to_string()))?;
+                                serde_json::to_value(result).unwrap_or(Value::Null)
+                            }
+                            None => Value::Null,
+                        };
+                        if let Some(entry) = entry.as_mut() {
+                            entry.result = Some(payload.clone());
+                        }
+                        emit_to_target(state.event_sink().as_ref(), &emit_config.target, context.origin.as_deref(), &emit_config.event, payload)
+                            .map_err(|e| ActionError::Emit(e.to_string()))?;
+                    }
+                    _ => {
+                        if let Some(next_steps) = span.in_scope(|| self.execute_step_sync(step, &mut scope, entry.as_mut()))? {
+                            steps_to_process.extend(next_steps.iter().rev());
+                        }
                     }
-                    continue;
                 }
 
-                match self.execute_step_sync(step, &mut scope) {
-                    Ok(Some(next_steps)) => {
-                        steps_to_process.extend(next_steps.iter().rev());
+                if let (Some(mut entry), Some(before)) = (entry, snapshot_before) {
+                    entry.duration_us = started.elapsed().as_micros() as u64;
+                    diff_values("", &before, &snapshot(&scope), &mut entry.changes);
+                    if let Some(trace) = context.trace.as_mut() {
+                        trace.push(entry);
                     }
-                    Ok(None) => {},
-                    Err(e) => return Err(e),
                 }
             }
             
-            context.temp = scope.get_value("context").unwrap();
+            context.data = scope_value(&scope, "data");
+            context.temp = scope_value(&scope, "context");
             Ok(context)
         })
     }
@@ -90,49 +131,169 @@ impl ActionEngine {
     fn execute_step_sync<'a>(
         &self,
         step: &'a Step,
-        scope: &mut Scope<'a>
+        scope: &mut Scope<'a>,
+        entry: Option<&mut StepTrace>,
     ) -> Result<Option<&'a Vec<Step>>, ActionError> {
-        if let Some(path) = &step.set {
-            if let Some(expr) = &step.to {
-                let result = self.engine.eval_with_scope::<Dynamic>(scope, expr)
+        match step {
+            Step::Set(set) => {
+                let result = self.engine.eval_with_scope::<Dynamic>(scope, &set.to)
                     .map_err(|e| ActionError::Rhai(e.to_string()))?;
                 let json_result = serde_json::to_value(result).unwrap_or(Value::Null);
-                set_value_by_path(scope, path, json_result)?;
+                if let Some(entry) = entry {
+                    entry.result = Some(json_result.clone());
+                }
+                set_value_by_path(scope, &set.set, json_result)?;
+                Ok(None)
             }
-        } else if let Some(condition) = &step.condition {
-            let result = self.engine.eval_with_scope::<bool>(scope, condition).unwrap_or(false);
-            if result {
-                return Ok(step.then.as_ref());
-            } else {
-                return Ok(step.an_else.as_ref());
+            Step::If(branch) => {
+                let result = self.engine.eval_with_scope::<bool>(scope, &branch.condition).unwrap_or(false);
+                if let Some(entry) = entry {
+                    entry.result = Some(Value::Bool(result));
+                    entry.branch = Some(if result { "then" } else { "else" });
+                }
+                Ok(Some(if result { &branch.then } else { &branch.an_else }))
             }
+            // Асинхронные шаги выполняются в `run`.
+            Step::ActionRun(_) | Step::Emit(_) => Ok(None),
         }
-        
-        Ok(None)
     }
 }
 
+/// Создает запись трассировки для шага (без результата и длительности).
+fn trace_entry(step: &Step) -> StepTrace {
+    match step {
+        Step::ActionRun(action_run) => StepTrace::new(step.kind(), Some(&action_run.name), None),
+        Step::Emit(emit) => StepTrace::new(step.kind(), Some(&emit.event), emit.payload.as_deref()),
+        Step::Set(set) => StepTrace::new(step.kind(), Some(&set.set), Some(&set.to)),
+        Step::If(branch) => StepTrace::new(step.kind(), None, Some(&branch.condition)),
+    }
+}
+
+/// Создает спан `step` для логов: тип шага и его цель (путь, событие или под-роут).
+fn step_span(step: &Step) -> Span {
+    let target = match step {
+        Step::ActionRun(action_run) => Some(action_run.name.as_str()),
+        Step::Emit(emit) => Some(emit.event.as_str()),
+        Step::Set(set) => Some(set.set.as_str()),
+        Step::If(_) => None,
+    };
+    tracing::debug_span!("step", kind = step.kind(), target)
+}
+
+/// Состояние, изменения которого показываются в трассировке: `{ context, data }`.
+fn snapshot(scope: &Scope) -> Value {
+    serde_json::json!({
+        "context": scope_value(scope, "context"),
+        "data": scope_value(scope, "data"),
+    })
+}
+
+/// Переводит JSON в значение Rhai (объекты - в `Map`, массивы - в `Array`),
+/// чтобы выражения могли обращаться к полям: `body.text`, `data.todos.items.len()`.
+pub(crate) fn to_rhai(value: &Value) -> Dynamic {
+    rhai::serde::to_dynamic(value).unwrap_or(Dynamic::UNIT)
+}
+
+/// Создает область видимости шагов. `data`, `body`, `user` и `globals` - константы:
+/// выражения не могут менять их на месте, но `data.*` (как и `context.*`) меняется шагами `set`.
+fn build_scope(context: &Context, globals: &Value) -> Scope<'static> {
+    let mut scope = Scope::new();
+    scope.push_constant("globals", to_rhai(globals));
+    scope.push_constant("data", to_rhai(&context.data));
+    scope.push_constant("body", to_rhai(&context.body));
+    scope.push_constant("user", to_rhai(&context.user));
+    scope.push("context", to_rhai(&context.temp));
+    scope
+}
+
+/// Текущее значение переменной (`context` или `data`) из области видимости в виде JSON.
+fn scope_value(scope: &Scope, name: &str) -> Value {
+    scope.get_value::<Dynamic>(name)
+        .and_then(|value| rhai::serde::from_dynamic(&value).ok())
+        .unwrap_or(Value::Null)
+}
+
 fn set_value_by_path(scope: &mut Scope, full_path: &str, value: Value) -> Result<(), ActionError> {
     let mut parts = full_path.split('.').peekable();
     let root_name = parts.next().ok_or_else(|| ActionError::InvalidSetPath(full_path.to_string()))?;
-    if root_name != "context" {
+    if root_name != "context" && root_name != "data" {
         return Err(ActionError::NotMutable(full_path.to_string()));
     }
-    if let Some(root_val) = scope.get_value_mut::<Value>(root_name) {
-        let mut current = root_val;
-        while let Some(part) = parts.next() {
-            if parts.peek().is_none() {
-                if let Some(obj) = current.as_object_mut() {
-                    obj.insert(part.to_string(), value);
-                }
-                break;
-            } else {
-                if !current.is_object() {
-                    *current = Value::Object(Default::default());
-                }
-                current = current.as_object_mut().unwrap().entry(part).or_insert(Value::Object(Default::default()));
+    let mut root_val = scope_value(scope, root_name);
+    let mut current = &mut root_val;
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            if let Some(obj) = current.as_object_mut() {
+                obj.insert(part.to_string(), value);
+            }
+            break;
+        } else {
+            if !current.is_object() {
+                *current = Value::Object(Default::default());
             }
+            current = current.as_object_mut().unwrap().entry(part).or_insert(Value::Object(Default::default()));
         }
     }
+    // `data` - константа, поэтому ее значение заменяется целиком.
+    if scope.is_constant(root_name) == Some(true) {
+        let _ = scope.remove::<Dynamic>(root_name);
+        scope.push_constant(root_name, to_rhai(&root_val));
+    } else {
+        scope.set_value(root_name, to_rhai(&root_val));
+    }
     Ok(())
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    fn scope_for(data: Value, body: Value) -> Scope<'static> {
+        build_scope(&Context::new(data, body, Value::Null), &Value::Null)
+    }
+
+    #[test]
+    fn expressions_see_objects_and_arrays_as_rhai_values() {
+        let engine = ActionEngine::new().engine;
+        let mut scope = scope_for(json!({ "todos": { "items": [{ "text": "Milk" }, { "text": "Bread" }] } }), json!({ "text": "Eggs" }));
+        assert_eq!(engine.eval_with_scope::<String>(&mut scope, "body.text").unwrap(), "Eggs");
+        assert_eq!(engine.eval_with_scope::<i64>(&mut scope, "data.todos.items.len()").unwrap(), 2);
+        assert_eq!(engine.eval_with_scope::<String>(&mut scope, "data.todos.items[1].text").unwrap(), "Bread");
+        let payload = engine.eval_with_scope::<Dynamic>(&mut scope, "#{ text: body.text, count: data.todos.items.len() }").unwrap();
+        assert_eq!(serde_json::to_value(payload).unwrap(), json!({ "text": "Eggs", "count": 2 }));
+    }
+
+    #[test]
+    fn set_creates_nested_context_paths() {
+        let mut scope = scope_for(Value::Null, Value::Null);
+        set_value_by_path(&mut scope, "context.result.count", json!(3)).unwrap();
+        assert_eq!(scope_value(&scope, "context"), json!({ "result": { "count": 3 } }));
+    }
+
+    #[test]
+    fn set_rejects_read_only_roots() {
+        let mut scope = scope_for(Value::Null, json!({ "text": "Eggs" }));
+        assert!(matches!(set_value_by_path(&mut scope, "body.text", json!("x")), Err(ActionError::NotMutable(_))));
+    }
+
+    #[test]
+    fn set_changes_data_and_later_expressions_see_it() {
+        let engine = ActionEngine::new().engine;
+        let mut scope = scope_for(json!({ "todos": { "items": [] } }), Value::Null);
+        set_value_by_path(&mut scope, "data.todos.items", json!([{ "text": "Milk" }])).unwrap();
+        assert_eq!(scope_value(&scope, "data"), json!({ "todos": { "items": [{ "text": "Milk" }] } }));
+        assert_eq!(engine.eval_with_scope::<i64>(&mut scope, "data.todos.items.len()").unwrap(), 1);
+        // Само выражение `data` по-прежнему не меняет: только шаг `set`.
+        assert!(engine.eval_with_scope::<Dynamic>(&mut scope, "data.todos = 1").is_err());
+    }
+
+    #[test]
+    fn globals_are_visible_but_read_only() {
+        let engine = ActionEngine::new().engine;
+        let mut scope = build_scope(&Context::new(Value::Null, Value::Null, Value::Null), &json!({ "title": "Todos" }));
+        assert_eq!(engine.eval_with_scope::<String>(&mut scope, "globals.title").unwrap(), "Todos");
+        assert!(matches!(set_value_by_path(&mut scope, "globals.title", json!("x")), Err(ActionError::NotMutable(_))));
+    }
+}
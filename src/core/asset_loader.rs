@@ -9,11 +9,15 @@ use crate::core::manifest::{ComponentConfig, Manifest};
 #[derive(Clone, Debug)]
 pub struct ComponentAsset {
     pub template: String,
+    /// Скомпилированный Mustache-шаблон. Компилируем один раз при загрузке,
+    /// а не при каждом рендере.
+    pub compiled: mustache::Template,
     pub style: Option<String>,
 }
 
 /// `AssetLoader` - это сервис, который при старте приложения загружает
-/// все необходимые файлы в память.
+/// все необходимые файлы в память. В режиме горячей перезагрузки он
+/// создается заново и целиком подменяется в `AppState`.
 pub struct AssetLoader {
     components: HashMap<String, ComponentAsset>,
     // В будущем здесь может быть кэш для `run` скриптов и т.д.
@@ -53,7 +57,10 @@ impl AssetLoader {
                 None => None,
             };
 
-            components.insert(name.clone(), ComponentAsset { template, style });
+            let compiled = mustache::compile_str(&template)
+                .map_err(|e| format!("Failed to compile template for component '{}' at {:?}: {}", name, template_path, e))?;
+
+            components.insert(name.clone(), ComponentAsset { template, compiled, style });
         }
         
         Ok(Self { components })
@@ -6,13 +6,16 @@ use rusqlite::params;
 use serde_json::Value;
 use std::collections::HashMap;
 use std::path::PathBuf;
-use std::sync::Arc;
+use std::sync::{Arc, RwLock};
 
 use crate::core::errors::ConnectorError;
 use crate::core::manifest::ConnectorConfig;
 
 type Pool = r2d2::Pool<SqliteConnectionManager>;
 
+/// Типы коннекторов, которые умеет создавать `ConnectorManager::new`.
+pub const CONNECTOR_TYPES: &[&str] = &["sqlite", "in-memory"];
+
 /// Единый интерфейс для всех типов коннекторов.
 #[async_trait]
 pub trait DataConnector: Send + Sync {
@@ -88,9 +91,50 @@ impl DataConnector for SqliteConnector {
     }
 }
 
+/// Коннектор, хранящий данные в памяти процесса. Данные живут до перезапуска
+/// приложения; начальное состояние берется из `initial_state`.
+struct InMemoryConnector {
+    data: RwLock<Value>,
+}
+
+impl InMemoryConnector {
+    fn new(initial_state: &Value) -> Self {
+        // Пустое состояние в том же виде, что отдает SQLite-коннектор.
+        let data = if initial_state.is_null() {
+            serde_json::json!({ "items": [] })
+        } else {
+            initial_state.clone()
+        };
+        Self { data: RwLock::new(data) }
+    }
+}
+
+#[async_trait]
+impl DataConnector for InMemoryConnector {
+    async fn read(&self) -> Result<Value, ConnectorError> {
+        Ok(self.data.read().expect("InMemoryConnector lock poisoned").clone())
+    }
+
+    async fn write(&self, data: &Value) -> Result<(), ConnectorError> {
+        *self.data.write().expect("InMemoryConnector lock poisoned") = data.clone();
+        Ok(())
+    }
+}
+
 /// Менеджер, который управляет всеми коннекторами.
+///
+/// Набор коннекторов можно заменить на лету (`reload`), поэтому `get` отдает
+/// `Arc`: роут, начавшийся до перезагрузки, дорабатывает со старым коннектором.
 pub struct ConnectorManager {
-    connectors: HashMap<String, Arc<dyn DataConnector>>,
+    /// Папка для файлов sqlite-коннекторов. `None` - все коннекторы в памяти (`in_memory`).
+    data_path: Option<PathBuf>,
+    connectors: RwLock<HashMap<String, ManagedConnector>>,
+}
+
+/// Коннектор и настройки, из которых он создан.
+struct ManagedConnector {
+    config: ConnectorConfig,
+    connector: Arc<dyn DataConnector>,
 }
 
 impl ConnectorManager {
@@ -98,38 +142,128 @@ impl ConnectorManager {
         // Создаем папку для данных, если ее нет
         std::fs::create_dir_all(&data_path).expect("Failed to create data directory");
 
-        let mut connectors = HashMap::new();
-        for (name, config) in configs {
-            match config.connector_type.as_str() {
-                "sqlite" => {
-                    let collection_name = config.collection.as_deref().unwrap_or(name);
-                    let db_file = data_path.join(format!("{}.db", collection_name));
-                    
-                    let manager = SqliteConnectionManager::file(&db_file);
-                    let pool = Pool::new(manager)
-                        .map_err(|e| ConnectorError::PoolInitialization(e.to_string()))?;
-                    
-                    // Создаем таблицы при первом запуске
-                    let conn = pool.get().map_err(|e| ConnectorError::PoolConnection(e.to_string()))?;
-                    conn.execute_batch(
-                        "BEGIN;
-                         CREATE TABLE IF NOT EXISTS items (data TEXT NOT NULL);
-                         CREATE TABLE IF NOT EXISTS meta (id INTEGER PRIMARY KEY, data TEXT NOT NULL);
-                         COMMIT;"
-                    )?;
-
-                    connectors.insert(name.clone(), Arc::new(SqliteConnector { pool }) as Arc<dyn DataConnector>);
-                }
-                "in-memory" => {
-                    // Реализация InMemoryConnector будет следующим шагом
+        let manager = Self { data_path: Some(data_path), connectors: RwLock::new(HashMap::new()) };
+        manager.reload(configs)?;
+        Ok(manager)
+    }
+
+    /// Создает для каждого коннектора из манифеста хранилище в памяти, независимо
+    /// от его типа. Используется тестами роутов: файлы данных приложения не затрагиваются.
+    pub fn in_memory(configs: &HashMap<String, ConnectorConfig>) -> Self {
+        let manager = Self { data_path: None, connectors: RwLock::new(HashMap::new()) };
+        manager.reload(configs).expect("in-memory connectors cannot fail to initialize");
+        manager
+    }
+
+    pub fn get(&self, name: &str) -> Option<Arc<dyn DataConnector>> {
+        self.connectors.read().expect("ConnectorManager lock poisoned")
+            .get(name)
+            .map(|managed| managed.connector.clone())
+    }
+
+    /// Приводит набор коннекторов к `configs` (используется горячей перезагрузкой).
+    /// Новые и измененные коннекторы создаются заново, удаленные закрываются,
+    /// а коннекторы с прежними настройками остаются теми же объектами.
+    /// Данные измененного коннектора в памяти сбрасываются к `initial_state`;
+    /// sqlite-коннектор с той же `collection` открывает тот же файл.
+    /// Если какой-то коннектор не создается, набор не меняется.
+    pub(crate) fn reload(&self, configs: &HashMap<String, ConnectorConfig>) -> Result<(), ConnectorError> {
+        let mut created = HashMap::new();
+        {
+            let current = self.connectors.read().expect("ConnectorManager lock poisoned");
+            for (name, config) in configs {
+                if current.get(name).is_some_and(|managed| managed.config == *config) {
+                    continue;
                 }
-                _ => return Err(ConnectorError::UnsupportedType(config.connector_type.clone())),
+                let connector = self.create(name, config)?;
+                created.insert(name.clone(), ManagedConnector { config: config.clone(), connector });
             }
         }
-        Ok(Self { connectors })
+
+        let mut current = self.connectors.write().expect("ConnectorManager lock poisoned");
+        current.retain(|name, _| configs.contains_key(name));
+        current.extend(created);
+        Ok(())
     }
 
-    pub fn get(&self, name: &str) -> Option<&Arc<dyn DataConnector>> {
-        self.connectors.get(name)
+    fn create(&self, name: &str, config: &ConnectorConfig) -> Result<Arc<dyn DataConnector>, ConnectorError> {
+        let Some(data_path) = &self.data_path else {
+            return Ok(Arc::new(InMemoryConnector::new(&config.initial_state)));
+        };
+
+        match config.connector_type.as_str() {
+            "sqlite" => {
+                let collection_name = config.collection.as_deref().unwrap_or(name);
+                let db_file = data_path.join(format!("{}.db", collection_name));
+                
+                let manager = SqliteConnectionManager::file(&db_file);
+                let pool = Pool::new(manager)
+                    .map_err(|e| ConnectorError::PoolInitialization(e.to_string()))?;
+                
+                // Создаем таблицы при первом запуске
+                let conn = pool.get().map_err(|e| ConnectorError::PoolConnection(e.to_string()))?;
+                conn.execute_batch(
+                    "BEGIN;
+                     CREATE TABLE IF NOT EXISTS items (data TEXT NOT NULL);
+                     CREATE TABLE IF NOT EXISTS meta (id INTEGER PRIMARY KEY, data TEXT NOT NULL);
+                     COMMIT;"
+                )?;
+
+                Ok(Arc::new(SqliteConnector { pool }))
+            }
+            "in-memory" => Ok(Arc::new(InMemoryConnector::new(&config.initial_state))),
+            _ => Err(ConnectorError::UnsupportedType(config.connector_type.clone())),
+        }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::core::test_support::TempApp;
+
+    fn configs(value: Value) -> HashMap<String, ConnectorConfig> {
+        serde_json::from_value(value).unwrap()
+    }
+
+    #[tokio::test]
+    async fn reload_rebuilds_only_added_and_changed_connectors() {
+        let manager = ConnectorManager::in_memory(&configs(json!({
+            "todos": { "type": "in-memory", "initial_state": { "items": [] } },
+            "settings": { "type": "in-memory", "initial_state": { "theme": "light" } },
+            "old": { "type": "in-memory" }
+        })));
+        let todos = manager.get("todos").unwrap();
+        todos.write(&json!({ "items": [1] })).await.unwrap();
+
+        manager.reload(&configs(json!({
+            "todos": { "type": "in-memory", "initial_state": { "items": [] } },
+            "settings": { "type": "in-memory", "initial_state": { "theme": "dark" } },
+            "users": { "type": "in-memory", "initial_state": { "items": ["admin"] } }
+        }))).unwrap();
+
+        // Не изменился - тот же коннектор с теми же данными.
+        assert!(Arc::ptr_eq(&manager.get("todos").unwrap(), &todos));
+        assert_eq!(manager.get("todos").unwrap().read().await.unwrap(), json!({ "items": [1] }));
+        assert_eq!(manager.get("settings").unwrap().read().await.unwrap(), json!({ "theme": "dark" }));
+        assert_eq!(manager.get("users").unwrap().read().await.unwrap(), json!({ "items": ["admin"] }));
+        assert!(manager.get("old").is_none());
+    }
+
+    #[test]
+    fn failed_reload_keeps_previous_connectors() {
+        let app = TempApp::new();
+        let manager = ConnectorManager::new(&configs(json!({ "todos": { "type": "in-memory" } })), app.path().join("data")).unwrap();
+
+        let error = manager.reload(&configs(json!({
+            "notes": { "type": "in-memory" },
+            "remote": { "type": "http" }
+        }))).unwrap_err();
+
+        assert!(error.to_string().contains("http"), "{}", error);
+        assert!(manager.get("todos").is_some());
+        assert!(manager.get("notes").is_none());
+    }
+}
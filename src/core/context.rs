@@ -1,6 +1,8 @@
 // src/core/context.rs
 use serde_json::Value;
 
+use crate::core::trace::StepTrace;
+
 #[derive(Debug, Clone)]
 pub struct Context {
     /// Данные, прочитанные из коннекторов (`reads`)
@@ -11,6 +13,10 @@ pub struct Context {
     pub user: Value,
     /// Временное хранилище для промежуточных вычислений
     pub temp: Value,
+    /// Метка окна, из которого вызвано действие (если оно пришло из окна)
+    pub origin: Option<String>,
+    /// Записи о выполненных шагах. `None`, если трассировка выключена
+    pub trace: Option<Vec<StepTrace>>,
 }
 
 impl Context {
@@ -20,6 +26,8 @@ impl Context {
             body,
             user,
             temp: Value::Object(Default::default()), // Начинаем с пустого объекта
+            origin: None,
+            trace: None,
         }
     }
 }
\ No newline at end of file
@@ -30,11 +30,14 @@ pub enum ActionError {
     #[error("Rhai script evaluation error: {0}")]
     Rhai(String),
 
-    #[error("Path '{0}' is not mutable. Only 'context.*' can be changed via 'set'.")]
+    #[error("Path '{0}' is not mutable. Only 'context.*' and 'data.*' can be changed via 'set'.")]
     NotMutable(String),
 
     #[error("Invalid or empty path provided for 'set' step: '{0}'")]
     InvalidSetPath(String),
+
+    #[error("Failed to emit event from 'emit' step: {0}")]
+    Emit(String),
 }
 
 /// Ошибки, возникающие во время рендеринга HTML-компонентов.
@@ -64,10 +67,22 @@ pub enum AppError {
     #[error("Renderer Error: {0}")]
     Render(#[from] RenderError),
 
-    #[error("Configuration Error in manifest.json: {0}")]
+    #[error("Configuration Error in manifest: {0}")]
     Config(String),
 }
 
+impl AppError {
+    /// Короткое имя вида ошибки для логов (поле `error` спана роута).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AppError::Connector(_) => "connector",
+            AppError::Action(_) => "action",
+            AppError::Render(_) => "render",
+            AppError::Config(_) => "config",
+        }
+    }
+}
+
 // Реализуем `serde::Serialize` для `AppError`.
 // Это критически важно, чтобы Tauri мог корректно сериализовать нашу ошибку
 // в JSON и отправить ее в JavaScript-фронтенд в случае `Err`.
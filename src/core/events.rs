@@ -0,0 +1,118 @@
+// taurifest/src/core/events.rs
+
+use serde_json::Value;
+use tauri::{AppHandle, Emitter, EventTarget, Manager};
+
+use crate::core::errors::AppError;
+use crate::core::manifest::EmitTarget;
+
+/// Куда движок отправляет события (`emit`, сокеты, трассировка).
+///
+/// В приложении это `TauriEventSink`, который отправляет события в окна.
+/// Без Tauri (тесты, CLI) можно подставить свою реализацию
+/// через `Engine::set_event_sink`.
+pub trait EventSink: Send + Sync {
+    /// Отправляет событие всем слушателям.
+    fn emit(&self, event: &str, payload: Value) -> Result<(), AppError>;
+
+    /// Отправляет событие одному окну.
+    fn emit_to(&self, window_label: &str, event: &str, payload: Value) -> Result<(), AppError>;
+
+    /// Отправляет событие всем слушателям, кроме окна `excluded_label`.
+    fn emit_except(&self, excluded_label: &str, event: &str, payload: Value) -> Result<(), AppError>;
+
+    /// Метки открытых окон.
+    fn window_labels(&self) -> Vec<String>;
+}
+
+/// Отправляет события в окна приложения Tauri.
+pub struct TauriEventSink {
+    app_handle: AppHandle,
+}
+
+impl TauriEventSink {
+    pub fn new(app_handle: AppHandle) -> Self {
+        Self { app_handle }
+    }
+}
+
+impl EventSink for TauriEventSink {
+    fn emit(&self, event: &str, payload: Value) -> Result<(), AppError> {
+        self.app_handle.emit(event, payload).map_err(emit_error)
+    }
+
+    fn emit_to(&self, window_label: &str, event: &str, payload: Value) -> Result<(), AppError> {
+        self.app_handle.emit_to(EventTarget::labeled(window_label), event, payload).map_err(emit_error)
+    }
+
+    fn emit_except(&self, excluded_label: &str, event: &str, payload: Value) -> Result<(), AppError> {
+        // Слушатели, не привязанные к окну (например, обработчики в Rust), тоже получают событие.
+        self.app_handle.emit_filter(event, payload, |candidate| match candidate {
+            EventTarget::AnyLabel { label }
+            | EventTarget::Window { label }
+            | EventTarget::Webview { label }
+            | EventTarget::WebviewWindow { label } => label != excluded_label,
+            _ => true,
+        }).map_err(emit_error)
+    }
+
+    fn window_labels(&self) -> Vec<String> {
+        self.app_handle.webview_windows().into_keys().collect()
+    }
+}
+
+fn emit_error(error: tauri::Error) -> AppError {
+    AppError::Config(format!("Tauri event emit failed: {}", error))
+}
+
+/// Отбрасывает все события. Используется движком, пока приложение Tauri
+/// не подставило свой получатель, и по умолчанию в режиме без окон.
+pub struct NullEventSink;
+
+impl EventSink for NullEventSink {
+    fn emit(&self, _event: &str, _payload: Value) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    fn emit_to(&self, _window_label: &str, _event: &str, _payload: Value) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    fn emit_except(&self, _excluded_label: &str, _event: &str, _payload: Value) -> Result<(), AppError> {
+        Ok(())
+    }
+
+    fn window_labels(&self) -> Vec<String> {
+        Vec::new()
+    }
+}
+
+/// Отправляет событие адресату `target`. Для `others` события получают и
+/// слушатели, не привязанные к окну; для `origin` без исходного окна
+/// событие не отправляется.
+pub(crate) fn emit_to_target(
+    sink: &dyn EventSink,
+    target: &EmitTarget,
+    origin: Option<&str>,
+    event_name: &str,
+    payload: Value,
+) -> Result<(), AppError> {
+    match (target, origin) {
+        (EmitTarget::All, _) | (EmitTarget::Others, None) => sink.emit(event_name, payload),
+        (EmitTarget::Others, Some(origin)) => sink.emit_except(origin, event_name, payload),
+        (EmitTarget::Origin, Some(origin)) => sink.emit_to(origin, event_name, payload),
+        (EmitTarget::Origin, None) => Ok(()),
+        (EmitTarget::Window(label), _) => sink.emit_to(label, event_name, payload),
+    }
+}
+
+/// Метки окон, которые получат событие с адресатом `target`.
+pub(crate) fn target_labels(sink: &dyn EventSink, target: &EmitTarget, origin: Option<&str>) -> Vec<String> {
+    let all = sink.window_labels().into_iter();
+    match target {
+        EmitTarget::All => all.collect(),
+        EmitTarget::Others => all.filter(|label| Some(label.as_str()) != origin).collect(),
+        EmitTarget::Origin => origin.map(|label| vec![label.to_string()]).unwrap_or_default(),
+        EmitTarget::Window(label) => vec![label.clone()],
+    }
+}
@@ -0,0 +1,48 @@
+// taurifest/src/core/hooks.rs
+
+use serde_json::{json, Value};
+use tauri::{AppHandle, Manager, Window};
+
+use crate::core::errors::AppError;
+use crate::core::pipeline;
+use crate::AppState;
+
+/// Выполняет хук `onStart`. Вызывается из `setup` и блокирует его,
+/// чтобы к моменту работы окон начальные данные уже были записаны.
+pub fn on_start(app_handle: &AppHandle) -> Result<(), AppError> {
+    let state = app_handle.state::<AppState>();
+    let Some(route_name) = state.manifest().hooks.on_start.clone() else { return Ok(()) };
+
+    tracing::info!(route = %route_name, "Running onStart hook");
+    tauri::async_runtime::block_on(pipeline::run_route_by_name(&state, &route_name, Value::Null, None))?;
+    Ok(())
+}
+
+/// Выполняет хук `onExit`. Блокирует завершение, пока роут не отработает.
+pub fn on_exit(app_handle: &AppHandle) {
+    let state = app_handle.state::<AppState>();
+    let Some(route_name) = state.manifest().hooks.on_exit.clone() else { return };
+
+    tracing::info!(route = %route_name, "Running onExit hook");
+    if let Err(e) = tauri::async_runtime::block_on(pipeline::run_route_by_name(&state, &route_name, Value::Null, None)) {
+        tracing::error!(route = %route_name, error = %e, "onExit hook failed");
+    }
+}
+
+/// Выполняет хук `onWindowCreated` для нового окна. Окно считается исходным
+/// (`origin`), так что шаги `emit` с `"target": "origin"` попадут именно в него.
+pub fn on_window_created(window: Window) {
+    let app_handle = window.app_handle().clone();
+    let label = window.label().to_string();
+
+    tauri::async_runtime::spawn(async move {
+        let state = app_handle.state::<AppState>();
+        let Some(route_name) = state.manifest().hooks.on_window_created.clone() else { return };
+
+        tracing::info!(route = %route_name, window = %label, "Running onWindowCreated hook");
+        let body = json!({ "label": label });
+        if let Err(e) = pipeline::run_route_by_name(&state, &route_name, body, Some(&label)).await {
+            tracing::error!(route = %route_name, window = %label, error = %e, "onWindowCreated hook failed");
+        }
+    });
+}
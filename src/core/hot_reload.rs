@@ -0,0 +1,109 @@
+// taurifest/src/core/hot_reload.rs
+
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::time::Duration;
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use serde_json::json;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::core::asset_loader::AssetLoader;
+use crate::core::errors::AppError;
+use crate::core::manifest::{Manifest, MANIFEST_FILES};
+use crate::AppState;
+
+/// Событие, которое получает фронтенд после успешной перезагрузки.
+pub const RELOAD_EVENT: &str = "taurifest://reload";
+
+/// Сколько ждать "хвоста" событий файловой системы. Редакторы часто сохраняют
+/// файл в несколько приемов (запись во временный файл, переименование и т.д.),
+/// и мы не хотим перезагружаться на каждый из них.
+const SETTLE_DELAY: Duration = Duration::from_millis(150);
+
+/// Запускает наблюдение за манифестом, файлами из его `include`
+/// и папкой `components` в фоновом потоке.
+pub fn watch(app_path: PathBuf, app_handle: AppHandle) -> Result<(), AppError> {
+    // notify сообщает абсолютные пути; с ними и сравниваем.
+    let app_path = std::path::absolute(&app_path).unwrap_or(app_path);
+    let (tx, rx) = mpsc::channel::<notify::Result<Event>>();
+
+    let mut watcher = notify::recommended_watcher(tx)
+        .map_err(|e| AppError::Config(format!("Failed to start file watcher: {}", e)))?;
+
+    // Следим за самой папкой, а не за файлом манифеста: многие редакторы
+    // сохраняют файл через переименование, и наблюдение за файлом при этом теряется.
+    // Рекурсивно - потому что файлы из `include` могут лежать в любых подпапках;
+    // лишние события отсеивает `collect_changes`.
+    watcher.watch(&app_path, RecursiveMode::Recursive)
+        .map_err(|e| AppError::Config(format!("Failed to watch {:?}: {}", app_path, e)))?;
+
+    tracing::info!(path = ?app_path, "Watching for changes");
+
+    std::thread::spawn(move || {
+        // Наблюдатель должен жить, пока жив поток, иначе события перестанут приходить.
+        let _watcher = watcher;
+
+        while let Ok(first) = rx.recv() {
+            // Шаблоны `include` берем из текущего манифеста: после перезагрузки они могли измениться.
+            let manifest = app_handle.state::<AppState>().manifest();
+            let mut changed = Vec::new();
+            collect_changes(&app_path, &manifest, first, &mut changed);
+            while let Ok(next) = rx.recv_timeout(SETTLE_DELAY) {
+                collect_changes(&app_path, &manifest, next, &mut changed);
+            }
+
+            if !changed.is_empty() {
+                reload(&app_path, &app_handle, changed);
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Перечитывает манифест и ассеты и подменяет их в `AppState` вместе
+/// с новыми и измененными коннекторами.
+/// Если новая версия не загружается, старая остается в работе.
+fn reload(app_path: &Path, app_handle: &AppHandle, changed: Vec<String>) {
+    let state = app_handle.state::<AppState>();
+    let loaded = Manifest::load(app_path, state.profile()).and_then(|manifest| {
+        let asset_loader = AssetLoader::new(app_path, &manifest).map_err(AppError::Config)?;
+        state.replace_loaded(manifest, asset_loader)
+    });
+
+    match loaded {
+        Ok(()) => {
+            tracing::info!(files = ?changed, "Reloaded");
+
+            if let Err(e) = app_handle.emit(RELOAD_EVENT, json!({ "files": changed })) {
+                tracing::warn!(event = RELOAD_EVENT, error = %e, "Failed to emit reload event");
+            }
+        }
+        Err(e) => tracing::error!(error = %e, "Reload failed, keeping previous version"),
+    }
+}
+
+/// Отбирает из события файловой системы изменения, которые нас интересуют,
+/// и добавляет их пути (относительно папки приложения) в `changed`.
+fn collect_changes(app_path: &Path, manifest: &Manifest, event: notify::Result<Event>, changed: &mut Vec<String>) {
+    let Ok(event) = event else { return };
+    if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)) {
+        return;
+    }
+
+    let components_path = app_path.join("components");
+    for path in event.paths {
+        let relevant = path.starts_with(&components_path)
+            || (path.parent() == Some(app_path) && path.file_name().is_some_and(|name| MANIFEST_FILES.iter().any(|file| name == *file)))
+            || manifest.includes(app_path, &path);
+        if !relevant {
+            continue;
+        }
+
+        let relative = path.strip_prefix(app_path).unwrap_or(&path).to_string_lossy().into_owned();
+        if !changed.contains(&relative) {
+            changed.push(relative);
+        }
+    }
+}
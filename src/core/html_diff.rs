@@ -0,0 +1,265 @@
+// taurifest/src/core/html_diff.rs
+
+use std::collections::{HashMap, HashSet};
+
+use scraper::node::Node;
+use scraper::{ElementRef, Html};
+use serde::Serialize;
+
+/// Одна операция над DOM, которую фронтенд применяет внутри обновляемого компонента.
+/// Элементы адресуются по атрибуту `key`, а если его нет - по `id`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "op", rename_all = "lowercase")]
+pub enum Patch {
+    /// Заменить элемент целиком (`outerHTML`).
+    Replace { key: String, html: String },
+    /// Удалить элемент.
+    Remove { key: String },
+    /// Вставить новый элемент после `after` или, если его нет, перед `before`.
+    Insert {
+        key: String,
+        html: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        after: Option<String>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        before: Option<String>,
+    },
+}
+
+/// Вычисляет минимальный список патчей, превращающий `old_html` в `new_html`.
+///
+/// Сравниваются только "внешние" элементы с ключом (`key`/`id`); все остальное
+/// (разметка вокруг них) должно совпадать. Подряд идущие элементы с ключами
+/// образуют группу, и ее место в разметке тоже должно совпадать. Если это не так,
+/// ключи переставлены местами или перешли в другую группу, или новый элемент
+/// негде привязать к соседям с ключами, возвращается `None` - фронтенд должен
+/// заменить HTML целиком.
+pub fn diff(old_html: &str, new_html: &str) -> Option<Vec<Patch>> {
+    if old_html == new_html {
+        return Some(Vec::new());
+    }
+
+    let old = Scan::of(old_html)?;
+    let new = Scan::of(new_html)?;
+    if old.skeleton != new.skeleton {
+        return None;
+    }
+
+    let old_by_key: HashMap<&str, &KeyedNode> = old.keyed.iter().map(|n| (n.key.as_str(), n)).collect();
+    let new_keys: HashSet<&str> = new.keyed.iter().map(|n| n.key.as_str()).collect();
+
+    // Общие ключи должны остаться в той же группе и в том же порядке.
+    let old_order = old.keyed.iter().filter(|n| new_keys.contains(n.key.as_str()));
+    let new_order = new.keyed.iter().filter(|n| old_by_key.contains_key(n.key.as_str()));
+    if !old_order.map(|n| (&n.key, n.group)).eq(new_order.map(|n| (&n.key, n.group))) {
+        return None;
+    }
+
+    let mut patches: Vec<Patch> = old.keyed.iter()
+        .filter(|n| !new_keys.contains(n.key.as_str()))
+        .map(|n| Patch::Remove { key: n.key.clone() })
+        .collect();
+
+    for (index, node) in new.keyed.iter().enumerate() {
+        match old_by_key.get(node.key.as_str()) {
+            Some(previous) if previous.html != node.html => {
+                patches.push(Patch::Replace { key: node.key.clone(), html: node.html.clone() });
+            }
+            Some(_) => {}
+            None => {
+                // Патчи применяются по порядку, поэтому предыдущий сосед уже будет в DOM,
+                // даже если он сам был только что вставлен. Соседи ищутся только в своей
+                // группе: между группами стоит разметка без ключей, и якорь за ее пределами
+                // поставил бы элемент не по ту сторону от нее.
+                let after = new.keyed[..index].iter().rev()
+                    .find(|n| n.group == node.group)
+                    .map(|n| n.key.clone());
+                let before = match after {
+                    Some(_) => None,
+                    None => Some(new.keyed[index + 1..].iter()
+                        .find(|n| n.group == node.group && old_by_key.contains_key(n.key.as_str()))?
+                        .key.clone()),
+                };
+                patches.push(Patch::Insert { key: node.key.clone(), html: node.html.clone(), after, before });
+            }
+        }
+    }
+
+    Some(patches)
+}
+
+/// Элемент с ключом и его положение в "скелете" документа.
+struct KeyedNode {
+    key: String,
+    html: String,
+    /// Номер группы подряд идущих элементов с ключами (у одного родителя,
+    /// без текста и элементов без ключа между ними).
+    group: usize,
+}
+
+/// Отметка группы элементов с ключами в скелете. `\0` не может встретиться
+/// в разобранном HTML: парсер заменяет его на U+FFFD.
+const GROUP_MARKER: &str = "\0";
+
+/// Результат обхода фрагмента: разметка, где каждая группа элементов с ключами
+/// заменена отметкой, и сами эти элементы.
+struct Scan {
+    skeleton: String,
+    keyed: Vec<KeyedNode>,
+    group_count: usize,
+}
+
+impl Scan {
+    /// Разбирает HTML. Возвращает `None`, если ключи в нем не уникальны.
+    fn of(html: &str) -> Option<Self> {
+        let document = Html::parse_fragment(html);
+        let mut scan = Scan { skeleton: String::new(), keyed: Vec::new(), group_count: 0 };
+        scan.walk(document.root_element());
+
+        let mut seen = HashSet::new();
+        scan.keyed.iter().all(|n| seen.insert(n.key.as_str())).then_some(scan)
+    }
+
+    fn walk(&mut self, node: ElementRef<'_>) {
+        // Продолжается ли группа элементов с ключами; пробелы между ними ее не прерывают.
+        let mut in_group = false;
+        for child in node.children() {
+            match child.value() {
+                Node::Text(text) => {
+                    let collapsed = text.split_whitespace().collect::<Vec<_>>().join(" ");
+                    if !collapsed.is_empty() {
+                        self.skeleton.push_str(&collapsed);
+                        in_group = false;
+                    }
+                }
+                Node::Element(element) => {
+                    let Some(element_ref) = ElementRef::wrap(child) else { continue };
+                    if let Some(key) = element.attr("key").or_else(|| element.attr("id")) {
+                        if !in_group {
+                            self.group_count += 1;
+                            self.skeleton.push_str(GROUP_MARKER);
+                            in_group = true;
+                        }
+                        self.keyed.push(KeyedNode { key: key.to_string(), html: element_ref.html(), group: self.group_count });
+                        continue;
+                    }
+
+                    in_group = false;
+                    self.skeleton.push('<');
+                    self.skeleton.push_str(element.name());
+                    for (name, value) in element.attrs() {
+                        self.skeleton.push_str(&format!(" {}=\"{}\"", name, value));
+                    }
+                    self.skeleton.push('>');
+                    self.walk(element_ref);
+                    self.skeleton.push_str(&format!("</{}>", element.name()));
+                }
+                _ => {}
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn insert(key: &str, html: &str, after: Option<&str>, before: Option<&str>) -> Patch {
+        Patch::Insert {
+            key: key.to_string(),
+            html: html.to_string(),
+            after: after.map(str::to_string),
+            before: before.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn unchanged_html_gives_no_patches() {
+        let html = r#"<ul><li key="a">A</li></ul>"#;
+        assert_eq!(diff(html, html), Some(Vec::new()));
+    }
+
+    #[test]
+    fn changed_item_is_replaced() {
+        let old = r#"<ul><li key="a">A</li><li key="b">B</li></ul>"#;
+        let new = r#"<ul><li key="a">A</li><li key="b">B!</li></ul>"#;
+        assert_eq!(
+            diff(old, new),
+            Some(vec![Patch::Replace { key: "b".into(), html: r#"<li key="b">B!</li>"#.into() }])
+        );
+    }
+
+    #[test]
+    fn inserted_items_are_anchored_to_keyed_neighbours() {
+        let old = r#"<ul><li key="b">B</li></ul>"#;
+        let new = r#"<ul><li key="a">A</li><li key="b">B</li><li key="c">C</li></ul>"#;
+        assert_eq!(
+            diff(old, new),
+            Some(vec![
+                insert("a", r#"<li key="a">A</li>"#, None, Some("b")),
+                insert("c", r#"<li key="c">C</li>"#, Some("b"), None),
+            ])
+        );
+    }
+
+    #[test]
+    fn removed_item_is_removed() {
+        let old = r#"<ul><li key="a">A</li><li key="b">B</li></ul>"#;
+        let new = r#"<ul><li key="b">B</li></ul>"#;
+        assert_eq!(diff(old, new), Some(vec![Patch::Remove { key: "a".into() }]));
+    }
+
+    #[test]
+    fn reorder_falls_back_to_full_html() {
+        let old = r#"<ul><li key="a">A</li><li key="b">B</li></ul>"#;
+        let new = r#"<ul><li key="b">B</li><li key="a">A</li></ul>"#;
+        assert_eq!(diff(old, new), None);
+    }
+
+    #[test]
+    fn changed_markup_outside_keys_falls_back_to_full_html() {
+        let old = r#"<h2>Todos</h2><ul><li key="a">A</li></ul>"#;
+        let new = r#"<h2>Done</h2><ul><li key="a">A</li></ul>"#;
+        assert_eq!(diff(old, new), None);
+    }
+
+    #[test]
+    fn keyed_item_moved_past_unkeyed_sibling_falls_back_to_full_html() {
+        let old = r#"<div><h2>Title</h2><p key="a">A</p><footer>F</footer></div>"#;
+        let new = r#"<div><p key="a">A</p><h2>Title</h2><footer>F</footer></div>"#;
+        assert_eq!(diff(old, new), None);
+
+        let old = r#"<div>Intro<p key="a">A</p></div>"#;
+        let new = r#"<div><p key="a">A</p>Intro</div>"#;
+        assert_eq!(diff(old, new), None);
+    }
+
+    #[test]
+    fn insert_before_unkeyed_header_falls_back_to_full_html() {
+        let old = r#"<div><h2>Title</h2><p key="a">A</p></div>"#;
+        let new = r#"<div><p key="b">B</p><h2>Title</h2><p key="a">A</p></div>"#;
+        assert_eq!(diff(old, new), None);
+    }
+
+    #[test]
+    fn insert_between_groups_uses_neighbours_of_its_own_group() {
+        let old = r#"<div><p key="a">A</p><hr><p key="c">C</p></div>"#;
+        let new = r#"<div><p key="a">A</p><hr><p key="b">B</p><p key="c">C</p></div>"#;
+        assert_eq!(diff(old, new), Some(vec![insert("b", r#"<p key="b">B</p>"#, None, Some("c"))]));
+    }
+
+    #[test]
+    fn insert_without_keyed_neighbour_falls_back_to_full_html() {
+        // Группа после `<hr>` целиком заменена: новому элементу не к чему привязаться.
+        let old = r#"<div><p key="a">A</p><hr><p key="c">C</p></div>"#;
+        let new = r#"<div><p key="a">A</p><hr><p key="d">D</p></div>"#;
+        assert_eq!(diff(old, new), None);
+    }
+
+    #[test]
+    fn duplicate_keys_fall_back_to_full_html() {
+        let old = r#"<ul><li key="a">A</li></ul>"#;
+        let new = r#"<ul><li key="a">A</li><li key="a">A2</li></ul>"#;
+        assert_eq!(diff(old, new), None);
+    }
+}
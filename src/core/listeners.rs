@@ -0,0 +1,41 @@
+// taurifest/src/core/listeners.rs
+
+use serde_json::Value;
+use tauri::{AppHandle, Listener, Manager};
+
+use crate::core::pipeline;
+use crate::AppState;
+
+/// Подписывает движок на события из секции `listeners` манифеста.
+/// Каждое событие запускает свой роут с payload события в качестве `body`.
+///
+/// Набор событий фиксируется при старте, а роут ищется в момент прихода
+/// события, поэтому горячая перезагрузка может переназначить роут, но
+/// новые события начнут слушаться только после перезапуска.
+pub fn register(app_handle: &AppHandle) {
+    let manifest = app_handle.state::<AppState>().manifest();
+
+    for event_name in manifest.listeners.keys() {
+        let handle = app_handle.clone();
+        let event_name = event_name.clone();
+
+        app_handle.listen_any(event_name.clone(), move |event| {
+            // JS может прислать как JSON, так и пустой payload.
+            let body = serde_json::from_str::<Value>(event.payload()).unwrap_or(Value::Null);
+            let handle = handle.clone();
+            let event_name = event_name.clone();
+
+            tauri::async_runtime::spawn(async move {
+                let state = handle.state::<AppState>();
+                let Some(route_name) = state.manifest().listeners.get(&event_name).cloned() else {
+                    return;
+                };
+
+                tracing::info!(event = %event_name, route = %route_name, "Event received");
+                if let Err(e) = pipeline::run_route_by_name(&state, &route_name, body, None).await {
+                    tracing::error!(event = %event_name, route = %route_name, error = %e, "Listener route failed");
+                }
+            });
+        });
+    }
+}
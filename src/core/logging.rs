@@ -0,0 +1,39 @@
+// taurifest/src/core/logging.rs
+
+use std::fs::{self, OpenOptions};
+use std::sync::Mutex;
+
+use tauri::{AppHandle, Manager};
+use tracing_subscriber::fmt::format::FmtSpan;
+
+use crate::core::errors::AppError;
+
+/// Имя файла журнала в папке данных приложения.
+pub const LOG_FILE_NAME: &str = "taurifest.log";
+
+/// Устанавливает глобальный подписчик `tracing`, который пишет события и
+/// закрытие спанов (с длительностью) построчно в JSON в `<app data dir>/taurifest.log`.
+///
+/// Возвращает ошибку, если подписчик уже установлен приложением.
+pub fn install_file_subscriber(app_handle: &AppHandle) -> Result<(), AppError> {
+    let log_dir = app_handle.path().app_data_dir()
+        .map_err(|e| AppError::Config(format!("Failed to resolve app data dir for logs: {}", e)))?;
+    fs::create_dir_all(&log_dir)
+        .map_err(|e| AppError::Config(format!("Failed to create log directory {:?}: {}", log_dir, e)))?;
+
+    let log_path = log_dir.join(LOG_FILE_NAME);
+    let file = OpenOptions::new().create(true).append(true).open(&log_path)
+        .map_err(|e| AppError::Config(format!("Failed to open log file {:?}: {}", log_path, e)))?;
+
+    tracing_subscriber::fmt()
+        .json()
+        .with_writer(Mutex::new(file))
+        .with_max_level(tracing::Level::DEBUG)
+        .with_span_events(FmtSpan::CLOSE)
+        .with_current_span(true)
+        .try_init()
+        .map_err(|e| AppError::Config(format!("Failed to install log subscriber: {}", e)))?;
+
+    tracing::info!(path = ?log_path, "Logging to file");
+    Ok(())
+}
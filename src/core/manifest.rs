@@ -1,36 +1,296 @@
 // src/core/manifest.rs
 
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
+use std::path::{Path, PathBuf};
+
+use crate::core::errors::AppError;
+use crate::core::profiles;
 
 // --- Главная структура манифеста ---
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct Manifest {
     #[serde(default)] // Если поле отсутствует, используется значение по умолчанию
     pub globals: serde_json::Value,
     #[serde(default)]
     pub sockets: HashMap<String, SocketConfig>,
     pub auth: Option<AuthConfig>,
+    #[serde(default)]
     pub connectors: HashMap<String, ConnectorConfig>,
+    #[serde(default)]
     pub components: HashMap<String, ComponentConfig>,
+    #[serde(default)]
     pub routes: HashMap<String, Route>,
+    #[serde(default)]
+    pub pages: HashMap<String, PageConfig>,
+    /// Tauri-события (из JS или других плагинов), запускающие роуты:
+    /// `"listeners": { "file-dropped": "importFiles" }`. Payload события становится `body`.
+    #[serde(default)]
+    pub listeners: HashMap<String, String>,
+    /// Фоновые задачи: имя задачи -> роут и расписание.
+    #[serde(default)]
+    pub schedule: HashMap<String, ScheduleConfig>,
+    /// Роуты, привязанные к моментам жизненного цикла приложения.
+    #[serde(default)]
+    pub hooks: HooksConfig,
+    /// Файлы с частями манифеста: шаблоны путей относительно папки приложения,
+    /// `"include": ["routes/*.json", "connectors.json"]`. Из них берутся
+    /// `routes`, `components`, `connectors` и `sockets`.
+    #[serde(default)]
+    pub include: Vec<String>,
+    /// Профили (`dev`, `prod`, `test`, ...), которые поверх манифеста меняют
+    /// `globals` и настройки коннекторов. Выбирается через `Builder::profile`
+    /// или переменную окружения `TAURIFEST_PROFILE` (см. `core::profiles`).
+    #[serde(default)]
+    pub profiles: HashMap<String, ProfileConfig>,
+}
+
+/// Часть манифеста из файла, подключенного через `include`.
+#[derive(Debug, Deserialize, Default)]
+#[serde(deny_unknown_fields)]
+struct ManifestPart {
+    /// Разрешаем `"$schema"`, как и в основном манифесте.
+    #[serde(rename = "$schema", default)]
+    _schema: Option<serde::de::IgnoredAny>,
+    #[serde(default)]
+    routes: HashMap<String, Route>,
+    #[serde(default)]
+    components: HashMap<String, ComponentConfig>,
+    #[serde(default)]
+    connectors: HashMap<String, ConnectorConfig>,
+    #[serde(default)]
+    sockets: HashMap<String, SocketConfig>,
+}
+
+/// Имена, под которыми манифест ищется в папке приложения.
+pub const MANIFEST_FILES: &[&str] = &["manifest.json", "manifest.yaml", "manifest.yml", "manifest.toml"];
+
+/// Формат манифеста или подключенного через `include` файла, по расширению.
+/// Структура во всех форматах одна и та же; в YAML и TOML длинные
+/// Rhai-выражения удобно писать многострочными строками.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ManifestFormat {
+    Json,
+    Yaml,
+    Toml,
+}
+
+impl ManifestFormat {
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "json" => Some(ManifestFormat::Json),
+            "yaml" | "yml" => Some(ManifestFormat::Yaml),
+            "toml" => Some(ManifestFormat::Toml),
+            _ => None,
+        }
+    }
+
+    /// Парсит текст файла. Сообщение об ошибке содержит строку и столбец.
+    fn parse<T: DeserializeOwned>(self, content: &str) -> Result<T, String> {
+        match self {
+            ManifestFormat::Json => serde_json::from_str(content).map_err(|e| e.to_string()),
+            ManifestFormat::Yaml => serde_yaml_ng::from_str(content).map_err(|e| e.to_string()),
+            ManifestFormat::Toml => toml::from_str(content).map_err(|e| e.to_string()),
+        }
+    }
+}
+
+/// Читает файл и парсит его в формате, определенном по расширению.
+fn read_file<T: DeserializeOwned>(path: &Path, file: &str) -> Result<T, AppError> {
+    let format = ManifestFormat::from_path(path)
+        .ok_or_else(|| AppError::Config(format!("Unsupported format of {}: use .json, .yaml, .yml or .toml", file)))?;
+    let content = std::fs::read_to_string(path)
+        .map_err(|e| AppError::Config(format!("Failed to read {:?}: {}", path, e)))?;
+    format.parse(&content).map_err(|e| AppError::Config(format!("Failed to parse {}: {}", file, e)))
+}
+
+impl Manifest {
+    /// Находит манифест в папке приложения: `manifest.json`, `manifest.yaml`,
+    /// `manifest.yml` или `manifest.toml`. Несколько манифестов сразу - ошибка.
+    pub fn path(app_path: &Path) -> Result<PathBuf, AppError> {
+        let found: Vec<PathBuf> = MANIFEST_FILES.iter()
+            .map(|name| app_path.join(name))
+            .filter(|path| path.is_file())
+            .collect();
+        match found.as_slice() {
+            [path] => Ok(path.clone()),
+            [] => Err(AppError::Config(format!("No manifest found in {:?}: expected one of {}", app_path, MANIFEST_FILES.join(", ")))),
+            _ => Err(AppError::Config(format!(
+                "Several manifests found in {:?}: {}. Keep only one",
+                app_path,
+                found.iter().map(|path| path.display().to_string()).collect::<Vec<_>>().join(", ")
+            ))),
+        }
+    }
+
+    /// Читает и парсит манифест из папки приложения вместе с файлами из `include`,
+    /// накладывает профиль `profile` и подставляет `${env:VAR}`.
+    /// Используется как при старте (`Builder::build`), так и при горячей перезагрузке.
+    pub fn load(app_path: &Path, profile: Option<&str>) -> Result<Self, AppError> {
+        let manifest_path = Self::path(app_path)?;
+        let file = manifest_path.file_name().unwrap_or_default().to_string_lossy().into_owned();
+        let mut manifest: Manifest = read_file(&manifest_path, &file)?;
+        manifest.merge_includes(app_path, &manifest_path)?;
+        profiles::apply(&mut manifest, profile)?;
+        Ok(manifest)
+    }
+
+    /// Подмешивает секции из файлов `include`. Все совпадения ключей собираются
+    /// в одну ошибку, где для каждого указаны оба файла.
+    fn merge_includes(&mut self, app_path: &Path, manifest_path: &Path) -> Result<(), AppError> {
+        let mut files = BTreeSet::new();
+        for pattern in &self.include {
+            let paths = glob::glob(&include_pattern(app_path, pattern))
+                .map_err(|e| AppError::Config(format!("Invalid include pattern '{}': {}", pattern, e)))?;
+            let before = files.len();
+            for path in paths {
+                let path = path.map_err(|e| AppError::Config(format!("Failed to read included file: {}", e)))?;
+                if path != manifest_path {
+                    files.insert(path);
+                }
+            }
+            if files.len() == before {
+                tracing::warn!(pattern = %pattern, "Include pattern matched no files");
+            }
+        }
+
+        // Где впервые объявлен ключ вида `routes.addTodo`; без записи - в самом манифесте.
+        let mut origins: HashMap<String, String> = HashMap::new();
+        let mut duplicates = Vec::new();
+        let main = manifest_path.file_name().unwrap_or_default().to_string_lossy();
+        for path in files {
+            let file = path.strip_prefix(app_path).unwrap_or(&path).to_string_lossy().into_owned();
+            let part: ManifestPart = read_file(&path, &file)?;
+
+            let mut merger = Merger { main: &main, file: &file, origins: &mut origins, duplicates: &mut duplicates };
+            merger.section("routes", &mut self.routes, part.routes);
+            merger.section("components", &mut self.components, part.components);
+            merger.section("connectors", &mut self.connectors, part.connectors);
+            merger.section("sockets", &mut self.sockets, part.sockets);
+        }
+
+        if duplicates.is_empty() {
+            Ok(())
+        } else {
+            duplicates.sort();
+            Err(AppError::Config(format!("Duplicate keys in included files:\n{}", duplicates.join("\n"))))
+        }
+    }
+
+    /// Подключен ли файл `path` через `include` (для горячей перезагрузки).
+    pub fn includes(&self, app_path: &Path, path: &Path) -> bool {
+        self.include.iter()
+            .filter_map(|pattern| glob::Pattern::new(&include_pattern(app_path, pattern)).ok())
+            .any(|pattern| pattern.matches_path(path))
+    }
+
+    /// JSON Schema для `manifest.json`, построенная по этим типам.
+    /// Подключается в редакторе через `"$schema"` и дает автодополнение и проверку.
+    pub fn schema() -> serde_json::Value {
+        serde_json::to_value(schemars::schema_for!(Manifest)).unwrap_or_default()
+    }
+}
+
+/// Полный шаблон для `glob`: путь к папке приложения экранируется,
+/// чтобы `[` или `*` в нем не считались частью шаблона.
+fn include_pattern(app_path: &Path, pattern: &str) -> String {
+    let root: PathBuf = glob::Pattern::escape(&app_path.to_string_lossy()).into();
+    root.join(pattern).to_string_lossy().into_owned()
+}
+
+/// Переносит записи одной секции из подключенного файла в манифест.
+struct Merger<'a> {
+    /// Имя файла основного манифеста.
+    main: &'a str,
+    file: &'a str,
+    origins: &'a mut HashMap<String, String>,
+    duplicates: &'a mut Vec<String>,
+}
+
+impl Merger<'_> {
+    fn section<T>(&mut self, section: &str, target: &mut HashMap<String, T>, part: HashMap<String, T>) {
+        for (key, value) in part {
+            let place = format!("{}.{}", section, key);
+            if target.contains_key(&key) {
+                let first = self.origins.get(&place).map(String::as_str).unwrap_or(self.main);
+                self.duplicates.push(format!("{} is defined in both {} and {}", place, first, self.file));
+                continue;
+            }
+            self.origins.insert(place, self.file.to_string());
+            target.insert(key, value);
+        }
+    }
 }
 
 // --- Структуры для каждой секции ---
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct SocketConfig {
     pub watch: String,
     pub emit: EmitConfig,
+    /// Rhai-условие над записанными данными (`data`). Если оно ложно,
+    /// событие не отправляется. Например: `"when": "data.items.len() > 0"`.
+    pub when: Option<String>,
+    /// Каким окнам отправлять событие. По умолчанию - всем.
+    #[serde(default)]
+    pub target: EmitTarget,
+    /// Если задано, событие отправляется, только когда записей не было
+    /// это время (в миллисекундах), - одно, с самыми свежими данными.
+    #[serde(rename = "debounceMs")]
+    pub debounce_ms: Option<u64>,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Адресат события: `"all"`, `"others"`, `"origin"` или метка конкретного окна.
+/// `origin` - окно, из которого был вызван `run_action`, вызвавший запись.
+#[derive(Debug, Deserialize, Clone, Default, PartialEq, JsonSchema)]
+#[serde(from = "String")]
+pub enum EmitTarget {
+    #[default]
+    All,
+    Others,
+    Origin,
+    Window(String),
+}
+
+impl From<String> for EmitTarget {
+    fn from(value: String) -> Self {
+        match value.as_str() {
+            "all" => EmitTarget::All,
+            "others" => EmitTarget::Others,
+            "origin" => EmitTarget::Origin,
+            _ => EmitTarget::Window(value),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct EmitConfig {
     pub event: String,
-    pub payload: String,
+    pub payload: Option<PayloadConfig>,
+    /// Имя компонента, который нужно отрисовать из свежих данных и отправить
+    /// вместо сырых данных: payload события будет `{ component, html }`
+    /// (тот же формат, что и ответ `run_action`).
+    pub render: Option<String>,
+    /// Дополнительные коннекторы для рендера `render`. Записанный коннектор
+    /// (`watch`) доступен всегда.
+    #[serde(default)]
+    pub reads: Vec<String>,
+}
+
+/// Источник полезной нагрузки события.
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum PayloadConfig {
+    /// `"payload": "todos"` - все содержимое коннектора (`read()`).
+    Connector(String),
+    /// `"payload": { "expr": "data.items.len()" }` - Rhai-выражение над
+    /// записанными данными, без повторного чтения коннектора.
+    Expression { expr: String },
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct AuthConfig {
     #[serde(rename = "userConnector")]
     pub user_connector: String,
@@ -40,7 +300,30 @@ pub struct AuthConfig {
     pub password_field: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Профиль: что меняется в манифесте, когда он выбран.
+#[derive(Debug, Deserialize, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ProfileConfig {
+    /// Сливается с `globals`: объекты объединяются по ключам, остальные значения заменяются.
+    #[serde(default)]
+    pub globals: serde_json::Value,
+    /// Настройки коннекторов из `connectors`, которые профиль переопределяет.
+    #[serde(default)]
+    pub connectors: HashMap<String, ConnectorOverride>,
+}
+
+/// Поля `ConnectorConfig`, которые можно переопределить в профиле.
+/// `{ "todos": { "type": "in-memory" } }`
+#[derive(Debug, Deserialize, Clone, Default, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct ConnectorOverride {
+    #[serde(rename = "type")]
+    pub connector_type: Option<String>,
+    pub collection: Option<String>,
+    pub initial_state: Option<serde_json::Value>,
+}
+
+#[derive(Debug, Deserialize, Clone, PartialEq, JsonSchema)]
 pub struct ConnectorConfig {
     #[serde(rename = "type")]
     pub connector_type: String,
@@ -49,7 +332,7 @@ pub struct ConnectorConfig {
     pub initial_state: serde_json::Value,
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 #[serde(untagged)] // Позволяет парсить либо строку, либо объект
 pub enum ComponentConfig {
     Simple(String),
@@ -59,7 +342,7 @@ pub enum ComponentConfig {
     },
 }
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
 pub struct Route {
     #[serde(rename = "type")]
     pub route_type: String,
@@ -67,29 +350,496 @@ pub struct Route {
     pub reads: Vec<String>,
     #[serde(default)]
     pub writes: Vec<String>,
-    pub update: Option<String>,
+    pub update: Option<UpdateConfig>,
     #[serde(default)]
     pub steps: Vec<Step>,
     pub internal: Option<bool>,
     // ... другие поля роутов (layout, inject, auth)
 }
 
-#[derive(Debug, Deserialize, Clone)]
-pub struct Step {
-    // Используем Option, т.к. в шаге будет только одно из этих полей
-    pub set: Option<String>,
-    pub to: Option<String>,
-    #[serde(rename = "if")]
-    pub condition: Option<String>,
-    pub then: Option<Vec<Step>>,
-    #[serde(rename = "else")] // `else` - ключевое слово в Rust
-    pub an_else: Option<Vec<Step>>,
+/// Роуты жизненного цикла приложения.
+#[derive(Debug, Deserialize, Clone, Default, JsonSchema)]
+pub struct HooksConfig {
+    /// Запускается один раз при старте, до показа окон (начальные данные, миграции).
+    #[serde(rename = "onStart")]
+    pub on_start: Option<String>,
+    /// Запускается перед завершением приложения (сброс данных на диск и т.п.).
+    #[serde(rename = "onExit")]
+    pub on_exit: Option<String>,
+    /// Запускается для каждого нового окна; `body` - `{ "label": "<метка окна>" }`.
+    #[serde(rename = "onWindowCreated")]
+    pub on_window_created: Option<String>,
+}
+
+/// Периодический запуск роута. Задается либо интервалом `every`
+/// (`"500ms"`, `"30s"`, `"5m"`, `"2h"`, `"1d"`), либо cron-выражением `cron`
+/// с секундами (`"0 0 3 * * *"` - каждый день в 03:00 по локальному времени).
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct ScheduleConfig {
+    pub route: String,
+    pub every: Option<String>,
+    pub cron: Option<String>,
+    /// `body`, с которым запускается роут.
+    #[serde(default)]
+    pub body: serde_json::Value,
+}
+
+/// Описание страницы, из которой движок собирает входной HTML-документ.
+/// Страница `index` отдается по адресу `taurifest://localhost/`,
+/// остальные - по `taurifest://localhost/<имя>.html`.
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+pub struct PageConfig {
+    pub title: Option<String>,
+    /// Компонент-обертка. Корневые компоненты доступны в нем как
+    /// `{{{components.<имя>}}}`. Без `layout` они просто выводятся по порядку.
+    pub layout: Option<String>,
+    /// Корневые компоненты страницы.
+    #[serde(default)]
+    pub components: Vec<String>,
+    /// Коннекторы, данные которых нужны для первой отрисовки.
+    #[serde(default)]
+    pub reads: Vec<String>,
+}
+
+/// Какие компоненты перерисовать после выполнения роута.
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[serde(untagged)]
+pub enum UpdateConfig {
+    /// `"update": "todoList"` - один компонент, ответ `{ html: "..." }`.
+    Single(String),
+    /// `"update": ["todoList", "counter"]` - ответ `{ html: { todoList: "...", counter: "..." } }`.
+    List(Vec<String>),
+    /// `"update": { "todoList": "#list", "counter": ".badge" }` - то же, что список,
+    /// но с CSS-селектором цели для каждого компонента (возвращается в `targets`).
+    Targets(HashMap<String, String>),
+}
+
+impl UpdateConfig {
+    /// Возвращает пары (компонент, селектор цели).
+    /// Для списка порядок совпадает с объявлением, для объекта не гарантируется.
+    pub fn components(&self) -> Vec<(&str, Option<&str>)> {
+        match self {
+            UpdateConfig::Single(name) => vec![(name.as_str(), None)],
+            UpdateConfig::List(names) => names.iter().map(|name| (name.as_str(), None)).collect(),
+            UpdateConfig::Targets(targets) => targets.iter()
+                .map(|(name, target)| (name.as_str(), Some(target.as_str())))
+                .collect(),
+        }
+    }
+}
+
+/// Шаг роута. Вид шага определяется ключом: `set`, `if`, `action:run` или `emit`.
+/// В одном шаге может быть только один вид, лишние ключи - ошибка загрузки манифеста.
+///
+/// ```json
+/// { "set": "data.todos.items", "to": "data.todos.items + [body]" }
+/// { "if": "body.text == \"\"", "then": [...], "else": [...] }
+/// { "action:run": { "name": "recount" } }
+/// { "emit": { "event": "toast", "payload": "#{ text: \"Saved\" }" } }
+/// ```
+#[derive(Debug, Clone, JsonSchema)]
+pub enum Step {
     #[serde(rename = "action:run")]
-    pub action_run: Option<ActionRunStep>,
-    // ... другие типы шагов (run, http:get, auth:login, etc.)
+    ActionRun(ActionRunStep),
+    #[serde(rename = "emit")]
+    Emit(EmitStep),
+    #[serde(untagged)]
+    Set(SetStep),
+    #[serde(untagged)]
+    If(IfStep),
+}
+
+/// Ключи, по которым определяется вид шага.
+const STEP_KINDS: &[&str] = &["set", "if", "action:run", "emit"];
+
+impl Step {
+    /// Название вида шага, как оно пишется в манифесте.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Step::Set(_) => "set",
+            Step::If(_) => "if",
+            Step::ActionRun(_) => "action:run",
+            Step::Emit(_) => "emit",
+        }
+    }
+
+    fn from_value(value: serde_json::Value) -> Result<Self, String> {
+        let serde_json::Value::Object(map) = &value else {
+            return Err(format!("step must be an object, got {}", value));
+        };
+        let kinds: Vec<&str> = STEP_KINDS.iter().copied().filter(|kind| map.contains_key(*kind)).collect();
+        let kind = match kinds.as_slice() {
+            [kind] => *kind,
+            [] => {
+                let keys: Vec<&str> = map.keys().map(String::as_str).collect();
+                return Err(format!(
+                    "step has no known kind: expected one of `{}`, got keys `{}`",
+                    STEP_KINDS.join("`, `"), keys.join("`, `")
+                ));
+            }
+            _ => return Err(format!("step mixes several kinds (`{}`), split it into separate steps", kinds.join("`, `"))),
+        };
+
+        // `action:run` и `emit` - объект с единственным ключом, параметры лежат внутри.
+        if let Some(extra) = map.keys().find(|key| key.as_str() != kind)
+            && (kind == "action:run" || kind == "emit")
+        {
+            return Err(format!("unknown field `{}` in '{}' step, parameters go inside `{}`", extra, kind, kind));
+        }
+
+        let step = match kind {
+            "set" => serde_json::from_value(value).map(Step::Set),
+            "if" => serde_json::from_value(value).map(Step::If),
+            "action:run" => serde_json::from_value(map[kind].clone()).map(Step::ActionRun),
+            _ => serde_json::from_value(map[kind].clone()).map(Step::Emit),
+        };
+        step.map_err(|e| format!("invalid '{}' step: {}", kind, e))
+    }
+}
+
+// Вид шага выбирается по ключу вручную: `untagged` из serde на ошибку отвечает
+// только "data did not match any variant", а здесь нужно точное сообщение.
+impl<'de> Deserialize<'de> for Step {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let value = serde_json::Value::deserialize(deserializer)?;
+        Step::from_value(value).map_err(serde::de::Error::custom)
+    }
 }
 
-#[derive(Debug, Deserialize, Clone)]
+/// Шаг `set`: записывает результат Rhai-выражения `to` по пути `set`
+/// (`context.*` или `data.*`).
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct SetStep {
+    pub set: String,
+    pub to: String,
+}
+
+/// Шаг `if`: выполняет `then` или `else` в зависимости от Rhai-условия.
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct IfStep {
+    #[serde(rename = "if")]
+    pub condition: String,
+    #[serde(default)]
+    pub then: Vec<Step>,
+    #[serde(rename = "else", default)] // `else` - ключевое слово в Rust
+    pub an_else: Vec<Step>,
+}
+
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
 pub struct ActionRunStep {
     pub name: String,
-}
\ No newline at end of file
+}
+
+/// Шаг `emit`: отправляет событие в UI прямо из роута (тосты, прогресс, навигация).
+/// `{ "emit": { "event": "toast", "payload": "#{ text: \"Saved\" }", "target": "origin" } }`
+#[derive(Debug, Deserialize, Clone, JsonSchema)]
+#[serde(deny_unknown_fields)]
+pub struct EmitStep {
+    pub event: String,
+    /// Rhai-выражение, вычисляемое в текущем контексте шагов.
+    pub payload: Option<String>,
+    #[serde(default)]
+    pub target: EmitTarget,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::core::test_support::TempApp;
+
+    fn step_error(json: &str) -> String {
+        serde_json::from_str::<Step>(json).unwrap_err().to_string()
+    }
+
+    #[test]
+    fn steps_of_each_kind_are_parsed() {
+        let steps: Vec<Step> = serde_json::from_str(r#"[
+            { "set": "context.a", "to": "1" },
+            { "if": "true", "then": [{ "set": "context.b", "to": "2" }] },
+            { "action:run": { "name": "recount" } },
+            { "emit": { "event": "toast", "payload": "1", "target": "origin" } }
+        ]"#).unwrap();
+        let kinds: Vec<&str> = steps.iter().map(Step::kind).collect();
+        assert_eq!(kinds, ["set", "if", "action:run", "emit"]);
+    }
+
+    #[test]
+    fn mixed_step_kinds_are_rejected() {
+        assert_eq!(
+            step_error(r#"{ "set": "context.a", "to": "1", "if": "true" }"#),
+            "step mixes several kinds (`set`, `if`), split it into separate steps"
+        );
+    }
+
+    #[test]
+    fn step_without_known_kind_is_rejected() {
+        assert_eq!(
+            step_error(r#"{ "sett": "context.a", "to": "1" }"#),
+            "step has no known kind: expected one of `set`, `if`, `action:run`, `emit`, got keys `sett`, `to`"
+        );
+        assert_eq!(
+            step_error(r#"{ "to": "1" }"#),
+            "step has no known kind: expected one of `set`, `if`, `action:run`, `emit`, got keys `to`"
+        );
+    }
+
+    #[test]
+    fn set_without_to_is_rejected() {
+        assert_eq!(step_error(r#"{ "set": "context.a" }"#), "invalid 'set' step: missing field `to`");
+    }
+
+    #[test]
+    fn extra_key_next_to_action_run_is_rejected() {
+        assert_eq!(
+            step_error(r#"{ "action:run": { "name": "x" }, "name": "y" }"#),
+            "unknown field `name` in 'action:run' step, parameters go inside `action:run`"
+        );
+    }
+
+    #[test]
+    fn unknown_keys_in_if_and_nested_steps_are_rejected() {
+        assert_eq!(
+            step_error(r#"{ "if": "true", "then": [], "otherwise": [] }"#),
+            "invalid 'if' step: unknown field `otherwise`, expected one of `if`, `then`, `else`"
+        );
+        assert_eq!(
+            step_error(r#"{ "if": "true", "then": [{ "set": "context.a", "too": "1" }] }"#),
+            "invalid 'if' step: invalid 'set' step: unknown field `too`, expected `set` or `to`"
+        );
+    }
+
+    #[test]
+    fn unknown_key_inside_emit_is_rejected() {
+        assert_eq!(
+            step_error(r#"{ "emit": { "event": "x", "extra": 1 } }"#),
+            "invalid 'emit' step: unknown field `extra`, expected one of `event`, `payload`, `target`"
+        );
+    }
+
+    // --- include ---
+
+    const ROUTE: &str = r#"{ "type": "action" }"#;
+
+    fn route_file(names: &[&str]) -> String {
+        let routes: Vec<String> = names.iter().map(|name| format!(r#""{}": {}"#, name, ROUTE)).collect();
+        format!(r#"{{ "routes": {{ {} }} }}"#, routes.join(", "))
+    }
+
+    fn sorted_keys<T>(map: &HashMap<String, T>) -> Vec<&str> {
+        let mut keys: Vec<&str> = map.keys().map(String::as_str).collect();
+        keys.sort();
+        keys
+    }
+
+    #[test]
+    fn included_files_are_merged_into_the_manifest() {
+        let app = TempApp::new();
+        app.write("manifest.json", &format!(r#"{{
+            "include": ["routes/*.json", "connectors.json", "missing/*.json"],
+            "routes": {{ "main": {} }}
+        }}"#, ROUTE))
+            .write("routes/a.json", &route_file(&["a"]))
+            .write("routes/b.json", r#"{ "routes": { "b": { "type": "action" } }, "components": { "list": "list.html" } }"#)
+            .write("routes/notes.txt", "not included")
+            .write("connectors.json", r#"{ "connectors": { "todos": { "type": "in-memory" } } }"#);
+
+        let manifest = Manifest::load(app.path(), None).unwrap();
+        assert_eq!(sorted_keys(&manifest.routes), ["a", "b", "main"]);
+        assert_eq!(sorted_keys(&manifest.components), ["list"]);
+        assert_eq!(sorted_keys(&manifest.connectors), ["todos"]);
+
+        assert!(manifest.includes(app.path(), &app.path().join("routes/a.json")));
+        assert!(manifest.includes(app.path(), &app.path().join("connectors.json")));
+        assert!(!manifest.includes(app.path(), &app.path().join("routes/notes.txt")));
+    }
+
+    #[test]
+    fn pattern_matching_the_manifest_itself_does_not_include_it_twice() {
+        let app = TempApp::new();
+        app.write("manifest.json", &format!(r#"{{ "include": ["*.json"], "routes": {{ "main": {} }} }}"#, ROUTE))
+            .write("extra.json", &route_file(&["extra"]));
+
+        let manifest = Manifest::load(app.path(), None).unwrap();
+        assert_eq!(sorted_keys(&manifest.routes), ["extra", "main"]);
+    }
+
+    #[test]
+    fn duplicate_keys_across_files_are_reported_together() {
+        let app = TempApp::new();
+        app.write("manifest.json", &format!(r#"{{
+            "include": ["parts/*.json"],
+            "routes": {{ "add": {} }},
+            "components": {{ "list": "list.html" }}
+        }}"#, ROUTE))
+            // Файлы подмешиваются в порядке путей, поэтому `one` считается первым.
+            .write("parts/two.json", r#"{ "connectors": { "todos": { "type": "in-memory" } }, "components": { "list": "other.html" } }"#)
+            .write("parts/one.json", &format!(r#"{{ "routes": {{ "add": {} }}, "connectors": {{ "todos": {{ "type": "sqlite" }} }} }}"#, ROUTE));
+
+        let error = Manifest::load(app.path(), None).unwrap_err().to_string();
+        assert!(error.ends_with(
+            "Duplicate keys in included files:\n\
+             components.list is defined in both manifest.json and parts/two.json\n\
+             connectors.todos is defined in both parts/one.json and parts/two.json\n\
+             routes.add is defined in both manifest.json and parts/one.json"
+        ), "{}", error);
+    }
+
+    // --- Форматы ---
+
+    const JSON_MANIFEST: &str = r#"{
+        "globals": { "appName": "Todos", "limits": { "max": 10 } },
+        "connectors": { "todos": { "type": "sqlite", "collection": "items", "initial_state": { "items": [] } } },
+        "components": { "layout": "layout.html", "todoList": { "template": "todo-list.html", "style": "todo-list.css" } },
+        "routes": {
+            "addTodo": {
+                "type": "action", "reads": ["todos"], "writes": ["todos"], "update": "todoList",
+                "steps": [
+                    {
+                        "if": "body.text != \"\"",
+                        "then": [{ "set": "data.todos.items", "to": "data.todos.items + [body.text]" }],
+                        "else": [{ "emit": { "event": "toast", "payload": "\"empty\"" } }]
+                    }
+                ]
+            }
+        },
+        "sockets": {
+            "todosChanged": {
+                "watch": "todos", "emit": { "event": "todos:changed", "render": "todoList" },
+                "target": "others", "debounceMs": 50
+            }
+        }
+    }"#;
+
+    const YAML_MANIFEST: &str = r#"
+globals:
+  appName: Todos
+  limits:
+    max: 10
+connectors:
+  todos:
+    type: sqlite
+    collection: items
+    initial_state:
+      items: []
+components:
+  layout: layout.html
+  todoList:
+    template: todo-list.html
+    style: todo-list.css
+routes:
+  addTodo:
+    type: action
+    reads: [todos]
+    writes: [todos]
+    update: todoList
+    steps:
+      - if: body.text != ""
+        then:
+          - set: data.todos.items
+            to: data.todos.items + [body.text]
+        else:
+          - emit:
+              event: toast
+              payload: '"empty"'
+sockets:
+  todosChanged:
+    watch: todos
+    emit:
+      event: todos:changed
+      render: todoList
+    target: others
+    debounceMs: 50
+"#;
+
+    const TOML_MANIFEST: &str = r#"
+[globals]
+appName = "Todos"
+limits = { max = 10 }
+
+[connectors.todos]
+type = "sqlite"
+collection = "items"
+initial_state = { items = [] }
+
+[components]
+layout = "layout.html"
+todoList = { template = "todo-list.html", style = "todo-list.css" }
+
+[routes.addTodo]
+type = "action"
+reads = ["todos"]
+writes = ["todos"]
+update = "todoList"
+
+[[routes.addTodo.steps]]
+if = 'body.text != ""'
+then = [{ set = "data.todos.items", to = "data.todos.items + [body.text]" }]
+else = [{ emit = { event = "toast", payload = '"empty"' } }]
+
+[sockets.todosChanged]
+watch = "todos"
+emit = { event = "todos:changed", render = "todoList" }
+target = "others"
+debounceMs = 50
+"#;
+
+    /// Манифест в виде, не зависящем от порядка обхода `HashMap`, для сравнения.
+    fn summary(manifest: &Manifest) -> Vec<String> {
+        fn entries<T: std::fmt::Debug>(section: &str, map: &HashMap<String, T>) -> Vec<String> {
+            let mut lines: Vec<String> = map.iter().map(|(name, value)| format!("{}.{}: {:?}", section, name, value)).collect();
+            lines.sort();
+            lines
+        }
+        let mut lines = vec![format!("globals: {}", manifest.globals)];
+        lines.extend(entries("connectors", &manifest.connectors));
+        lines.extend(entries("components", &manifest.components));
+        lines.extend(entries("routes", &manifest.routes));
+        lines.extend(entries("sockets", &manifest.sockets));
+        lines
+    }
+
+    fn load_as(file: &str, content: &str) -> Result<Manifest, AppError> {
+        let app = TempApp::new();
+        app.write(file, content);
+        Manifest::load(app.path(), None)
+    }
+
+    #[test]
+    fn every_format_gives_the_same_manifest() {
+        let expected = summary(&load_as("manifest.json", JSON_MANIFEST).unwrap());
+        assert_eq!(summary(&load_as("manifest.yaml", YAML_MANIFEST).unwrap()), expected);
+        assert_eq!(summary(&load_as("manifest.yml", YAML_MANIFEST).unwrap()), expected);
+        assert_eq!(summary(&load_as("manifest.toml", TOML_MANIFEST).unwrap()), expected);
+    }
+
+    #[test]
+    fn parse_errors_point_to_line_and_column() {
+        let cases = [
+            (
+                "manifest.json",
+                "{\n  \"routes\": {\n    \"add\": { \"type\": \"action\", \"steps\": [{ \"set\": \"context.a\" }] }\n  }\n}\n",
+                "at line 3 column 64",
+            ),
+            ("manifest.yaml", "routes:\n  add:\n    type: action\n    steps:\n      - set: context.a\n", "at line 5 column 7"),
+            ("manifest.toml", "[routes.add]\ntype = \"action\"\n\n[[routes.add.steps]]\nset = \"context.a\"\n", "at line 4, column 1"),
+        ];
+        for (file, content, position) in cases {
+            let error = load_as(file, content).unwrap_err().to_string();
+            assert!(error.contains(&format!("Failed to parse {}", file)), "{}", error);
+            assert!(error.contains("invalid 'set' step: missing field `to`"), "{}", error);
+            assert!(error.contains(position), "{}", error);
+        }
+    }
+
+    #[test]
+    fn several_manifests_are_rejected() {
+        let app = TempApp::new();
+        app.write("manifest.json", "{}").write("manifest.toml", "");
+        let error = Manifest::load(app.path(), None).unwrap_err().to_string();
+        assert!(error.contains("Several manifests found"), "{}", error);
+    }
+}
+
@@ -8,4 +8,18 @@ pub mod context;
 pub mod action_engine;
 pub mod renderer;
 pub mod asset_loader;
-pub mod sockets;
\ No newline at end of file
+pub mod sockets;
+pub mod hot_reload;
+pub mod html_diff;
+pub mod pages;
+pub mod pipeline;
+pub mod listeners;
+pub mod scheduler;
+pub mod hooks;
+pub mod trace;
+pub mod logging;
+pub mod events;
+pub mod validation;
+pub mod profiles;
+#[cfg(test)]
+pub(crate) mod test_support;
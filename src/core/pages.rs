@@ -0,0 +1,282 @@
+// taurifest/src/core/pages.rs
+
+use serde_json::Value;
+
+use crate::core::errors::AppError;
+use crate::Engine;
+
+/// Клиентский runtime, который встраивается в каждую сгенерированную страницу.
+pub const RUNTIME_SCRIPT: &str = include_str!("../runtime/taurifest.js");
+
+/// Путь, по которому runtime отдается через протокол `taurifest://`,
+/// чтобы его можно было подключить к собственной странице через `<script src>`.
+pub const RUNTIME_PATH: &str = "/__taurifest/runtime.js";
+
+/// Собирает полный HTML-документ страницы из секции `pages` манифеста:
+/// корневые компоненты (или `layout` вокруг них), стили всех компонентов
+/// и клиентский runtime.
+///
+/// Корневые компоненты запоминаются как текущее состояние окна `window_label`:
+/// патчи следующих `run_action` считаются относительно того, что показывает
+/// только что загруженная страница, а не прошлые рендеры этого окна.
+pub async fn render_page(state: &Engine, page_name: &str, window_label: &str) -> Result<String, AppError> {
+    let manifest = state.manifest();
+    let page = manifest.pages.get(page_name)
+        .ok_or_else(|| AppError::Config(format!("Page '{}' not found in manifest", page_name)))?;
+
+    let owner = format!("page '{}'", page_name);
+    let data = state.read_connectors(&page.reads, &owner).await?;
+    let user = state.resolve_user().await?;
+    let mut render_context = serde_json::json!({
+        "data": data,
+        "user": user,
+        "globals": manifest.globals,
+    });
+
+    let asset_loader = state.asset_loader();
+
+    // Каждый корневой компонент оборачиваем в контейнер с `atom-component`,
+    // чтобы runtime знал, куда вставлять HTML, пришедший из `run_action`.
+    let mut components = serde_json::Map::new();
+    state.renderer.forget_window(window_label);
+    for component_name in &page.components {
+        let html = state.renderer.render_component(&asset_loader, component_name, &render_context).await?;
+        state.renderer.remember(window_label, component_name, &html);
+        components.insert(
+            component_name.clone(),
+            Value::String(format!("<div atom-component=\"{}\">{}</div>", component_name, html)),
+        );
+    }
+
+    // `layout` тоже получает контейнер: без него его стилям было бы не к чему привязаться.
+    let body = match &page.layout {
+        Some(layout) => {
+            render_context["components"] = Value::Object(components);
+            let html = state.renderer.render_component(&asset_loader, layout, &render_context).await?;
+            format!("<div atom-component=\"{}\">{}</div>", layout, html)
+        }
+        None => page.components.iter()
+            .filter_map(|name| components.get(name).and_then(Value::as_str))
+            .collect::<Vec<_>>()
+            .join("\n"),
+    };
+
+    // Стили собираем со всех компонентов, а не только с корневых:
+    // `run_action` может позже отрисовать любой из них. Правила каждого компонента
+    // ограничиваются его контейнером, чтобы не задевать остальную страницу.
+    let mut styled: Vec<_> = manifest.components.keys()
+        .filter_map(|name| asset_loader.get_component(name).and_then(|c| c.style.as_deref()).map(|style| (name, style)))
+        .collect();
+    styled.sort_by_key(|(name, _)| name.as_str());
+    let styles = styled.iter()
+        .map(|(name, style)| format!("/* {} */\n{}", name, scope_css(style, &format!("[atom-component=\"{}\"]", name))))
+        .collect::<Vec<_>>()
+        .join("\n");
+
+    // Сокеты с `render` присылают готовый HTML: страница подписывается на них сама.
+    let mut render_events: Vec<&str> = manifest.sockets.values()
+        .filter(|socket| socket.emit.render.is_some())
+        .map(|socket| socket.emit.event.as_str())
+        .collect();
+    render_events.sort();
+    render_events.dedup();
+    let subscriptions = render_events.iter()
+        .map(|event| format!("taurifest.listen({});\n", Value::String(event.to_string())))
+        .collect::<String>();
+
+    let title = escape_html(page.title.as_deref().unwrap_or(page_name));
+
+    Ok(format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n<style>\n{}\n</style>\n</head>\n<body>\n{}\n<script>\n{}\n{}</script>\n</body>\n</html>\n",
+        title, styles, body, RUNTIME_SCRIPT, subscriptions
+    ))
+}
+
+/// Определяет имя страницы по пути запроса: `/` и `/index.html` -> `index`,
+/// `/todos.html` и `/todos` -> `todos`.
+pub fn page_name_from_path(path: &str) -> &str {
+    let name = path.trim_start_matches('/');
+    let name = name.strip_suffix(".html").unwrap_or(name);
+    if name.is_empty() { "index" } else { name }
+}
+
+/// Ограничивает CSS компонента селектором `scope`: каждый селектор правила
+/// получает его как предка, а `:scope` заменяется им самим (стиль контейнера).
+/// Блоки `@media`, `@supports`, `@container` и `@layer` обрабатываются рекурсивно,
+/// остальные at-правила (`@keyframes`, `@font-face`, ...) копируются как есть.
+fn scope_css(css: &str, scope: &str) -> String {
+    let css = strip_comments(css);
+    let mut result = String::new();
+    let mut rest = css.as_str();
+    loop {
+        let Some(open) = find_outside_strings(rest, &['{', ';']) else {
+            result.push_str(rest.trim());
+            break;
+        };
+        let prelude = rest[..open].trim();
+        if rest.as_bytes()[open] == b';' {
+            // `@import`, `@charset` и прочие at-правила без блока.
+            result.push_str(prelude);
+            result.push_str(";\n");
+            rest = &rest[open + 1..];
+            continue;
+        }
+
+        let close = matching_brace(rest, open).unwrap_or(rest.len());
+        let block = &rest[open + 1..close.min(rest.len())];
+        let at_rule = prelude.strip_prefix('@').map(|rule| rule.split(|c: char| !c.is_alphanumeric() && c != '-').next().unwrap_or(""));
+        match at_rule {
+            Some("media" | "supports" | "container" | "layer") => {
+                result.push_str(&format!("{} {{\n{}}}\n", prelude, scope_css(block, scope)));
+            }
+            Some(_) => result.push_str(&format!("{} {{{}}}\n", prelude, block)),
+            None => {
+                let selectors = split_selectors(prelude).into_iter()
+                    .map(|selector| if selector.contains(":scope") {
+                        selector.replace(":scope", scope)
+                    } else {
+                        format!("{} {}", scope, selector)
+                    })
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                result.push_str(&format!("{} {{{}}}\n", selectors, block));
+            }
+        }
+        rest = rest.get(close + 1..).unwrap_or("");
+    }
+    result
+}
+
+/// Убирает комментарии `/* ... */`. `/*` внутри строки в кавычках комментарием не считается.
+fn strip_comments(css: &str) -> String {
+    let mut result = String::new();
+    let mut rest = css;
+    while let Some(start) = find_outside_strings(rest, &['/']) {
+        if !rest[start..].starts_with("/*") {
+            result.push_str(&rest[..=start]);
+            rest = &rest[start + 1..];
+            continue;
+        }
+        result.push_str(&rest[..start]);
+        rest = rest[start + 2..].find("*/").map_or("", |end| &rest[start + 2 + end + 2..]);
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Позиция первого из символов `targets` вне строк в кавычках.
+fn find_outside_strings(text: &str, targets: &[char]) -> Option<usize> {
+    let mut quote = None;
+    for (index, c) in text.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None if c == '"' || c == '\'' => quote = Some(c),
+            None if targets.contains(&c) => return Some(index),
+            None => {}
+        }
+    }
+    None
+}
+
+/// Позиция `}`, закрывающей `{` в позиции `open`.
+fn matching_brace(text: &str, open: usize) -> Option<usize> {
+    let mut depth = 0;
+    let mut position = open;
+    loop {
+        let offset = find_outside_strings(&text[position..], &['{', '}'])?;
+        position += offset;
+        if text.as_bytes()[position] == b'{' {
+            depth += 1;
+        } else {
+            depth -= 1;
+            if depth == 0 {
+                return Some(position);
+            }
+        }
+        position += 1;
+    }
+}
+
+/// Делит список селекторов по запятым верхнего уровня
+/// (запятые внутри `:is(a, b)` и `[title="a, b"]` не считаются).
+fn split_selectors(prelude: &str) -> Vec<&str> {
+    let mut selectors = Vec::new();
+    let mut depth = 0;
+    let mut quote = None;
+    let mut start = 0;
+    for (index, c) in prelude.char_indices() {
+        match quote {
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '(' | '[' => depth += 1,
+                ')' | ']' => depth -= 1,
+                ',' if depth == 0 => {
+                    selectors.push(prelude[start..index].trim());
+                    start = index + 1;
+                }
+                _ => {}
+            },
+        }
+    }
+    selectors.push(prelude[start..].trim());
+    selectors.retain(|selector| !selector.is_empty());
+    selectors
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SCOPE: &str = "[atom-component=\"todoList\"]";
+
+    #[test]
+    fn selectors_are_prefixed_with_the_container() {
+        let css = "/* list */\nul, li.done { padding: 0; }\ninput[name=\"a,b\"], :is(h1, h2) > span { color: red; }";
+        assert_eq!(
+            scope_css(css, SCOPE),
+            "[atom-component=\"todoList\"] ul, [atom-component=\"todoList\"] li.done { padding: 0; }\n\
+             [atom-component=\"todoList\"] input[name=\"a,b\"], [atom-component=\"todoList\"] :is(h1, h2) > span { color: red; }\n"
+        );
+    }
+
+    #[test]
+    fn scope_pseudo_class_targets_the_container_itself() {
+        assert_eq!(
+            scope_css(":scope { display: block; }", SCOPE),
+            "[atom-component=\"todoList\"] { display: block; }\n"
+        );
+    }
+
+    #[test]
+    fn media_blocks_are_scoped_and_keyframes_are_kept() {
+        let css = "@media (max-width: 600px) { li { padding: 0; } }\n@keyframes fade { from { opacity: 0; } to { opacity: 1; } }";
+        assert_eq!(
+            scope_css(css, SCOPE),
+            "@media (max-width: 600px) {\n[atom-component=\"todoList\"] li { padding: 0; }\n}\n\
+             @keyframes fade { from { opacity: 0; } to { opacity: 1; } }\n"
+        );
+    }
+
+    #[test]
+    fn comment_markers_inside_strings_are_kept() {
+        assert_eq!(
+            scope_css("li { content: \"/*\"; } /* note */ a { b: c }", "[c]"),
+            "[c] li { content: \"/*\"; }\n[c] a { b: c }\n"
+        );
+    }
+
+    #[test]
+    fn braces_inside_strings_do_not_end_the_rule() {
+        assert_eq!(
+            scope_css("li::before { content: \"}\"; }", SCOPE),
+            "[atom-component=\"todoList\"] li::before { content: \"}\"; }\n"
+        );
+    }
+}
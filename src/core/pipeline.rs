@@ -0,0 +1,123 @@
+// taurifest/src/core/pipeline.rs
+
+use std::time::Instant;
+
+use serde_json::Value;
+use tracing::field::Empty;
+use tracing::Instrument;
+
+use crate::core::context::Context;
+use crate::core::errors::AppError;
+use crate::core::manifest::Route;
+use crate::core::trace::TRACE_EVENT;
+use crate::Engine;
+
+/// Выполняет роут целиком, кроме рендера: чтение `reads`, `steps`, запись `writes`
+/// и уведомление сокетов. Общий путь для `Engine::run_route` и для роутов, запускаемых
+/// не из окна (слушатели событий и т.д.).
+///
+/// `origin` - метка окна, из которого пришло действие, если оно есть.
+///
+/// Выполнение оборачивается в спан `route` с именем роута, `reads`/`writes`,
+/// длительностью и видом ошибки, если она произошла.
+pub async fn execute_route(
+    engine: &Engine,
+    name: &str,
+    route: &Route,
+    body: Value,
+    origin: Option<&str>,
+) -> Result<Context, AppError> {
+    let span = tracing::info_span!(
+        "route",
+        route = name,
+        origin = origin,
+        reads = ?route.reads,
+        writes = ?route.writes,
+        duration_ms = Empty,
+        error = Empty,
+    );
+
+    let started = Instant::now();
+    let result = execute_route_steps(engine, name, route, body, origin)
+        .instrument(span.clone())
+        .await;
+
+    span.record("duration_ms", started.elapsed().as_secs_f64() * 1000.0);
+    span.in_scope(|| match &result {
+        Ok(_) => tracing::info!("Route finished"),
+        Err(e) => {
+            span.record("error", e.kind());
+            tracing::warn!(error = %e, "Route failed");
+        }
+    });
+
+    result
+}
+
+async fn execute_route_steps(
+    engine: &Engine,
+    name: &str,
+    route: &Route,
+    body: Value,
+    origin: Option<&str>,
+) -> Result<Context, AppError> {
+    // --- Шаг 1: Прочитать все необходимые данные из коннекторов (`reads`) ---
+    let data = engine.read_connectors(&route.reads, &format!("action '{}'", name)).await?;
+
+    // --- Шаг 2: Создать начальный контекст выполнения ---
+    let user = engine.resolve_user().await?;
+    let mut initial_context = Context::new(data, body, user);
+    initial_context.origin = origin.map(str::to_string);
+    if engine.trace {
+        initial_context.trace = Some(Vec::new());
+    }
+
+    // --- Шаг 3: Запустить ActionEngine для выполнения `steps` ---
+    let final_context = engine.action_engine.run(&route.steps, initial_context, engine).await?;
+
+    // --- Шаг 4: Сохранить измененные данные и отправить real-time события ---
+    if let Some(data_object) = final_context.data.as_object() {
+        for connector_name in &route.writes {
+            if let (Some(connector), Some(data_to_write)) = (
+                engine.connector_manager.get(connector_name),
+                data_object.get(connector_name)
+            ) {
+                // Сначала асинхронно записываем новые данные в базу
+                connector.write(data_to_write)
+                    .instrument(tracing::debug_span!("connector.write", connector = connector_name.as_str()))
+                    .await?;
+
+                // Затем, после успешной записи, уведомляем SocketManager.
+                // Он проверит, нужно ли отправлять событие по этому поводу.
+                engine.socket_manager.notify_on_write(connector_name, data_to_write, origin, engine).await;
+            }
+        }
+    }
+
+    Ok(final_context)
+}
+
+/// Находит роут по имени в текущем манифесте и выполняет его.
+/// Используется триггерами, которым не нужен ответ для UI (события,
+/// расписание, хуки жизненного цикла).
+pub async fn run_route_by_name(
+    engine: &Engine,
+    name: &str,
+    body: Value,
+    origin: Option<&str>,
+) -> Result<Context, AppError> {
+    let manifest = engine.manifest();
+    let route = manifest.routes.get(name)
+        .ok_or_else(|| AppError::Config(format!("Route '{}' not found in manifest", name)))?;
+    let final_context = execute_route(engine, name, route, body, origin).await?;
+
+    // Ответа, в который можно положить трассировку, здесь нет - отправляем ее событием.
+    if let Some(steps) = &final_context.trace {
+        let payload = serde_json::json!({ "route": name, "steps": steps });
+        if let Err(e) = engine.event_sink().emit(TRACE_EVENT, payload) {
+            tracing::warn!(route = name, error = %e, "Failed to emit trace");
+        }
+    }
+
+    Ok(final_context)
+}
@@ -0,0 +1,208 @@
+// taurifest/src/core/profiles.rs
+//
+// Профили и переменные окружения в манифесте. Одна и та же папка приложения
+// работает в разработке и в продакшене: профиль меняет `globals` и коннекторы,
+// а `${env:VAR}` подставляет значения, которые не должны лежать в манифесте.
+
+use serde_json::Value;
+
+use crate::core::errors::AppError;
+use crate::core::manifest::Manifest;
+
+/// Переменная окружения с именем профиля.
+pub const PROFILE_ENV: &str = "TAURIFEST_PROFILE";
+
+/// Определяет профиль: `TAURIFEST_PROFILE`, если она задана и не пуста, иначе `explicit`.
+/// Переменная важнее значения из кода, чтобы собранное приложение можно было
+/// запустить с другим профилем без пересборки.
+pub fn select(explicit: Option<&str>) -> Option<String> {
+    std::env::var(PROFILE_ENV).ok()
+        .filter(|name| !name.is_empty())
+        .or_else(|| explicit.map(str::to_string))
+}
+
+/// Накладывает профиль `profile` на манифест и подставляет `${env:VAR}`
+/// в строки `globals` и настроек коннекторов.
+pub(crate) fn apply(manifest: &mut Manifest, profile: Option<&str>) -> Result<(), AppError> {
+    apply_with_env(manifest, profile, &|name| std::env::var(name).ok())
+}
+
+/// То же, что [`apply`], но значения переменных берутся из `env`, а не из окружения процесса.
+fn apply_with_env(manifest: &mut Manifest, profile: Option<&str>, env: &dyn Fn(&str) -> Option<String>) -> Result<(), AppError> {
+    if let Some(name) = profile {
+        let overlay = manifest.profiles.get(name).cloned().ok_or_else(|| {
+            let mut available: Vec<&str> = manifest.profiles.keys().map(String::as_str).collect();
+            available.sort();
+            AppError::Config(format!("Profile '{}' is not defined in manifest (available: {})", name, available.join(", ")))
+        })?;
+
+        merge(&mut manifest.globals, overlay.globals);
+        for (connector_name, settings) in overlay.connectors {
+            let connector = manifest.connectors.get_mut(&connector_name).ok_or_else(|| {
+                AppError::Config(format!("profiles.{}.connectors: connector '{}' is not defined", name, connector_name))
+            })?;
+            if let Some(connector_type) = settings.connector_type {
+                connector.connector_type = connector_type;
+            }
+            if settings.collection.is_some() {
+                connector.collection = settings.collection;
+            }
+            if let Some(initial_state) = settings.initial_state {
+                connector.initial_state = initial_state;
+            }
+        }
+    }
+
+    interpolate_value(&mut manifest.globals, "globals", env)?;
+    for (name, connector) in &mut manifest.connectors {
+        let place = format!("connectors.{}", name);
+        connector.connector_type = interpolate(&connector.connector_type, &format!("{}.type", place), env)?;
+        if let Some(collection) = &connector.collection {
+            connector.collection = Some(interpolate(collection, &format!("{}.collection", place), env)?);
+        }
+        interpolate_value(&mut connector.initial_state, &format!("{}.initial_state", place), env)?;
+    }
+    Ok(())
+}
+
+/// Сливает `overlay` в `base`: объекты - по ключам (рекурсивно), остальное заменяется.
+/// `null` в профиле ничего не меняет.
+fn merge(base: &mut Value, overlay: Value) {
+    match (base, overlay) {
+        (_, Value::Null) => {}
+        (Value::Object(base_map), Value::Object(overlay_map)) => {
+            for (key, value) in overlay_map {
+                merge(base_map.entry(key).or_insert(Value::Null), value);
+            }
+        }
+        (base, overlay) => *base = overlay,
+    }
+}
+
+/// Подставляет переменные окружения во все строки внутри `value`.
+fn interpolate_value(value: &mut Value, place: &str, env: &dyn Fn(&str) -> Option<String>) -> Result<(), AppError> {
+    match value {
+        Value::String(text) => *text = interpolate(text, place, env)?,
+        Value::Array(items) => {
+            for (index, item) in items.iter_mut().enumerate() {
+                interpolate_value(item, &format!("{}[{}]", place, index), env)?;
+            }
+        }
+        Value::Object(map) => {
+            for (key, item) in map.iter_mut() {
+                interpolate_value(item, &format!("{}.{}", place, key), env)?;
+            }
+        }
+        _ => {}
+    }
+    Ok(())
+}
+
+/// Заменяет `${env:VAR}` значением `env(VAR)`.
+/// Незаданная переменная - ошибка: лучше не запуститься, чем уйти на пустой адрес.
+/// `$${env:VAR}` оставляет в строке сам текст `${env:VAR}`.
+fn interpolate(text: &str, place: &str, env: &dyn Fn(&str) -> Option<String>) -> Result<String, AppError> {
+    const OPEN: &str = "${env:";
+
+    let mut result = String::new();
+    let mut rest = text;
+    while let Some(start) = rest.find(OPEN) {
+        let after = &rest[start + OPEN.len()..];
+        if rest[..start].ends_with('$') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str(OPEN);
+            rest = after;
+            continue;
+        }
+        result.push_str(&rest[..start]);
+        let end = after.find('}')
+            .ok_or_else(|| AppError::Config(format!("{}: unclosed '{}' in '{}'", place, OPEN, text)))?;
+        let name = &after[..end];
+        let value = env(name)
+            .ok_or_else(|| AppError::Config(format!("{}: environment variable '{}' is not set", place, name)))?;
+        result.push_str(&value);
+        rest = &after[end + 1..];
+    }
+    result.push_str(rest);
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+
+    /// Окружение для тестов: процесс и его переменные не трогаем.
+    fn env(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+        let vars: HashMap<String, String> = vars.iter().map(|(name, value)| (name.to_string(), value.to_string())).collect();
+        move |name| vars.get(name).cloned()
+    }
+
+    #[test]
+    fn interpolate_substitutes_variables() {
+        let env = env(&[("HOST", "example.com"), ("PORT", "8080")]);
+        assert_eq!(
+            interpolate("https://${env:HOST}:${env:PORT}/api", "globals.url", &env).unwrap(),
+            "https://example.com:8080/api"
+        );
+        assert_eq!(interpolate("no variables", "globals.text", &env).unwrap(), "no variables");
+    }
+
+    #[test]
+    fn interpolate_fails_on_unset_variable() {
+        let error = interpolate("${env:MISSING}", "globals.key", &env(&[])).unwrap_err();
+        assert!(error.to_string().contains("globals.key: environment variable 'MISSING' is not set"));
+    }
+
+    #[test]
+    fn interpolate_fails_on_unclosed_tag() {
+        let error = interpolate("${env:NAME", "globals.key", &env(&[("NAME", "value")])).unwrap_err();
+        assert!(error.to_string().contains("unclosed"));
+    }
+
+    #[test]
+    fn interpolate_keeps_escaped_tag() {
+        assert_eq!(
+            interpolate("$${env:NAME} and ${env:ESCAPE}", "globals.text", &env(&[("ESCAPE", "value")])).unwrap(),
+            "${env:NAME} and value"
+        );
+    }
+
+    #[test]
+    fn apply_interpolates_globals_and_connector_settings() {
+        let mut manifest: Manifest = serde_json::from_value(json!({
+            "globals": { "api": { "urls": ["${env:API_URL}/v1"] } },
+            "connectors": { "todos": { "type": "${env:STORE}", "collection": "${env:PREFIX}_todos", "initial_state": { "owner": "${env:USER}" } } }
+        }))
+        .unwrap();
+        let env = env(&[("API_URL", "https://api"), ("STORE", "in-memory"), ("PREFIX", "test"), ("USER", "alice")]);
+        apply_with_env(&mut manifest, None, &env).unwrap();
+
+        assert_eq!(manifest.globals, json!({ "api": { "urls": ["https://api/v1"] } }));
+        let todos = &manifest.connectors["todos"];
+        assert_eq!(todos.connector_type, "in-memory");
+        assert_eq!(todos.collection.as_deref(), Some("test_todos"));
+        assert_eq!(todos.initial_state, json!({ "owner": "alice" }));
+    }
+
+    #[test]
+    fn merge_combines_objects_recursively() {
+        let mut base = json!({ "api": { "url": "http://localhost", "timeout": 5 }, "title": "Dev" });
+        merge(&mut base, json!({ "api": { "url": "https://prod" }, "title": "Prod", "extra": [1] }));
+        assert_eq!(base, json!({ "api": { "url": "https://prod", "timeout": 5 }, "title": "Prod", "extra": [1] }));
+    }
+
+    #[test]
+    fn merge_ignores_null_and_replaces_other_values() {
+        let mut base = json!({ "items": [1, 2], "name": "base" });
+        merge(&mut base, json!({ "items": [3], "name": null }));
+        assert_eq!(base, json!({ "items": [3], "name": "base" }));
+
+        let mut base = json!("text");
+        merge(&mut base, json!({ "key": 1 }));
+        assert_eq!(base, json!({ "key": 1 }));
+    }
+}
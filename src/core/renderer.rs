@@ -1,23 +1,70 @@
 // taurifest/src/core/renderer.rs - УПРОЩЕННАЯ И РАБОЧАЯ ВЕРСИЯ
 
+use std::collections::HashMap;
+use std::sync::Mutex;
+
 use rhai::{Engine, Scope};
 use scraper::Html;
 use serde_json::Value;
 
+use crate::core::action_engine::to_rhai;
 use crate::core::asset_loader::AssetLoader;
 use crate::core::errors::RenderError;
+use crate::core::html_diff::{self, Patch};
 
 pub struct Renderer {
     rhai_engine: Engine,
+    /// Последний отданный HTML каждого компонента, отдельно для каждого окна:
+    /// (метка окна, имя компонента) -> HTML. Нужен для вычисления патчей.
+    last_rendered: Mutex<HashMap<(String, String), String>>,
+}
+
+impl Default for Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Renderer {
     pub fn new() -> Self {
         let mut engine = Engine::new();
         engine.set_max_operations(100_000);
-        Self { rhai_engine: engine }
+        Self { rhai_engine: engine, last_rendered: Mutex::new(HashMap::new()) }
+    }
+
+    /// Запоминает `html` как текущее состояние компонента в окне `window_label`
+    /// и возвращает патчи относительно предыдущего состояния.
+    /// `None` означает, что патч построить нельзя (первый рендер или структура
+    /// изменилась слишком сильно) и фронтенд должен использовать полный HTML.
+    pub fn diff_with_last(&self, window_label: &str, component_name: &str, html: &str) -> Option<Vec<Patch>> {
+        let previous = self.remember(window_label, component_name, html)?;
+        html_diff::diff(&previous, html)
+    }
+
+    /// Запоминает `html` как текущее состояние компонента в окне, не вычисляя патчи.
+    /// Возвращает предыдущее запомненное состояние.
+    pub fn remember(&self, window_label: &str, component_name: &str, html: &str) -> Option<String> {
+        let key = (window_label.to_string(), component_name.to_string());
+        self.last_rendered.lock()
+            .expect("Renderer cache lock poisoned")
+            .insert(key, html.to_string())
+    }
+
+    /// Забывает все, что запомнено для окна `window_label`. После перезагрузки
+    /// страницы DOM окна уже не совпадает с прошлыми рендерами, и патчи
+    /// относительно них вставили бы элементы повторно.
+    pub fn forget_window(&self, window_label: &str) {
+        self.last_rendered.lock()
+            .expect("Renderer cache lock poisoned")
+            .retain(|(label, _), _| label != window_label);
     }
 
+    /// Забывает рендеры всех окон (например, после горячей перезагрузки шаблонов).
+    pub fn forget_all(&self) {
+        self.last_rendered.lock().expect("Renderer cache lock poisoned").clear();
+    }
+
+    #[tracing::instrument(name = "render", level = "debug", skip_all, fields(component = component_name))]
     pub async fn render_component(
         &self,
         asset_loader: &AssetLoader,
@@ -27,8 +74,8 @@ impl Renderer {
         let component_asset = asset_loader.get_component(component_name)
             .ok_or_else(|| RenderError::AssetNotFound(component_name.to_string()))?;
 
-        let template = mustache::compile_str(&component_asset.template)?;
-        
+        let template = &component_asset.compiled;
+
         let mustache_data = mustache::to_data(context)
             .expect("Internal Error: Failed to convert context Value to mustache::Data.");
 
@@ -40,9 +87,9 @@ impl Renderer {
 
         let mut document = Html::parse_fragment(&html_string);
         let mut scope = Scope::new();
-        scope.push_constant("data", context.get("data").cloned().unwrap_or(Value::Null));
-        scope.push_constant("user", context.get("user").cloned().unwrap_or(Value::Null));
-        scope.push_constant("globals", context.get("globals").cloned().unwrap_or(Value::Null));
+        for name in ["data", "user", "globals"] {
+            scope.push_constant(name, to_rhai(context.get(name).unwrap_or(&Value::Null)));
+        }
 
         let mut nodes_to_remove = Vec::new();
         let selector = scraper::Selector::parse("[atom-if]").unwrap();
@@ -68,6 +115,59 @@ impl Renderer {
             }
         }
 
-        Ok(document.html())
+        // `parse_fragment` оборачивает фрагмент в `<html>`; отдаем только его содержимое,
+        // чтобы HTML компонента можно было вставлять внутрь страницы и других компонентов.
+        Ok(document.root_element().inner_html())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn list(keys: &[&str]) -> String {
+        let items: String = keys.iter().map(|key| format!(r#"<li key="{0}">{0}</li>"#, key)).collect();
+        format!("<ul>{}</ul>", items)
+    }
+
+    #[test]
+    fn patches_are_computed_against_the_last_render_in_the_window() {
+        let renderer = Renderer::new();
+        assert_eq!(renderer.diff_with_last("main", "list", &list(&["a"])), None);
+        assert_eq!(
+            renderer.diff_with_last("main", "list", &list(&["a", "b"])),
+            Some(vec![Patch::Insert { key: "b".into(), html: r#"<li key="b">b</li>"#.into(), after: Some("a".into()), before: None }])
+        );
+        // У другого окна своя история.
+        assert_eq!(renderer.diff_with_last("other", "list", &list(&["a", "b"])), None);
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn reloaded_page_is_diffed_against_what_it_shows() {
+        // Окно видело [a, b], затем другое окно добавило `c`, и страница перезагрузилась:
+        // теперь она показывает [a, b, c], и `render_page` запоминает именно это.
+        let renderer = Renderer::new();
+        renderer.remember("main", "list", &list(&["a", "b"]));
+        renderer.forget_window("main");
+        renderer.remember("main", "list", &list(&["a", "b", "c"]));
+
+        // Добавление `d` вставляет только `d`, а не `c` еще раз.
+        assert_eq!(
+            renderer.diff_with_last("main", "list", &list(&["a", "b", "c", "d"])),
+            Some(vec![Patch::Insert { key: "d".into(), html: r#"<li key="d">d</li>"#.into(), after: Some("c".into()), before: None }])
+        );
+    }
+
+    #[test]
+    fn forgotten_window_gets_full_html() {
+        let renderer = Renderer::new();
+        renderer.remember("main", "list", &list(&["a"]));
+        renderer.remember("other", "list", &list(&["a"]));
+        renderer.forget_window("main");
+        assert_eq!(renderer.diff_with_last("main", "list", &list(&["a", "b"])), None);
+        assert!(renderer.diff_with_last("other", "list", &list(&["a", "b"])).is_some());
+
+        renderer.forget_all();
+        assert_eq!(renderer.diff_with_last("other", "list", &list(&["a", "b", "c"])), None);
+    }
+}
@@ -0,0 +1,151 @@
+// taurifest/src/core/scheduler.rs
+
+use std::str::FromStr;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono::Local;
+use tauri::{AppHandle, Manager};
+
+use crate::core::errors::AppError;
+use crate::core::manifest::ScheduleConfig;
+use crate::core::pipeline;
+use crate::AppState;
+
+/// Когда запускать задачу.
+enum Trigger {
+    Every(Duration),
+    Cron(Box<cron::Schedule>),
+}
+
+impl Trigger {
+    fn parse(job_name: &str, config: &ScheduleConfig) -> Result<Self, AppError> {
+        match (&config.every, &config.cron) {
+            (Some(every), None) => parse_duration(every)
+                .map(Trigger::Every)
+                .ok_or_else(|| AppError::Config(format!("Invalid 'every' value '{}' in schedule '{}'", every, job_name))),
+            (None, Some(expr)) => cron::Schedule::from_str(expr)
+                .map(|schedule| Trigger::Cron(Box::new(schedule)))
+                .map_err(|e| AppError::Config(format!("Invalid cron expression '{}' in schedule '{}': {}", expr, job_name, e))),
+            _ => Err(AppError::Config(format!("Schedule '{}' must have exactly one of 'every' or 'cron'", job_name))),
+        }
+    }
+
+    /// Сколько ждать до следующего запуска. `None` - запусков больше не будет.
+    fn next_delay(&self) -> Option<Duration> {
+        match self {
+            Trigger::Every(interval) => Some(*interval),
+            Trigger::Cron(schedule) => {
+                let next = schedule.upcoming(Local).next()?;
+                Some((next - Local::now()).to_std().unwrap_or_default())
+            }
+        }
+    }
+}
+
+/// Проверяет расписание задачи, не запуская ее.
+pub(crate) fn check(job_name: &str, config: &ScheduleConfig) -> Result<(), AppError> {
+    Trigger::parse(job_name, config).map(|_| ())
+}
+
+/// Запускает все задачи из секции `schedule` на runtime Tauri.
+/// Ошибки в расписании обнаруживаются сразу, до запуска первой задачи.
+///
+/// Если предыдущий запуск задачи еще не завершился, очередной пропускается.
+///
+/// Набор задач и их `every`/`cron` фиксируются при старте, а `route` и `body`
+/// берутся из текущего манифеста при каждом запуске, поэтому горячая перезагрузка
+/// может их поменять. Задача, удаленная из манифеста, останавливается; новые
+/// задачи и новое расписание начнут действовать только после перезапуска.
+pub fn start(app_handle: &AppHandle) -> Result<(), AppError> {
+    let manifest = app_handle.state::<AppState>().manifest();
+
+    let mut jobs = Vec::new();
+    for (job_name, config) in &manifest.schedule {
+        jobs.push((job_name.clone(), Trigger::parse(job_name, config)?));
+    }
+
+    for (job_name, trigger) in jobs {
+        let handle = app_handle.clone();
+        let running = Arc::new(AtomicBool::new(false));
+
+        tauri::async_runtime::spawn(async move {
+            while let Some(delay) = trigger.next_delay() {
+                tokio::time::sleep(delay).await;
+
+                let Some(config) = handle.state::<AppState>().manifest().schedule.get(&job_name).cloned() else {
+                    tracing::info!(job = %job_name, "Job was removed from manifest, stopping");
+                    break;
+                };
+
+                if running.swap(true, Ordering::SeqCst) {
+                    tracing::warn!(job = %job_name, "Skipping run: previous run is still in progress");
+                    continue;
+                }
+
+                let handle = handle.clone();
+                let running = running.clone();
+                let job_name = job_name.clone();
+                tauri::async_runtime::spawn(async move {
+                    let state = handle.state::<AppState>();
+                    tracing::info!(job = %job_name, route = %config.route, "Running scheduled job");
+                    if let Err(e) = pipeline::run_route_by_name(&state, &config.route, config.body.clone(), None).await {
+                        tracing::error!(job = %job_name, error = %e, "Scheduled job failed");
+                    }
+                    running.store(false, Ordering::SeqCst);
+                });
+            }
+        });
+    }
+
+    Ok(())
+}
+
+/// Разбирает интервал вида `500ms`, `30s`, `5m`, `2h`, `1d`.
+fn parse_duration(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let split = text.find(|c: char| !c.is_ascii_digit())?;
+    let (number, unit) = text.split_at(split);
+    let number: u64 = number.parse().ok()?;
+
+    // Переполнение при пересчете в секунды - такая же ошибка, как и неизвестная единица.
+    let duration = match unit.trim() {
+        "ms" => Duration::from_millis(number),
+        "s" => Duration::from_secs(number),
+        "m" => Duration::from_secs(number.checked_mul(60)?),
+        "h" => Duration::from_secs(number.checked_mul(60 * 60)?),
+        "d" => Duration::from_secs(number.checked_mul(60 * 60 * 24)?),
+        _ => return None,
+    };
+    (!duration.is_zero()).then_some(duration)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_duration_understands_units() {
+        assert_eq!(parse_duration("500ms"), Some(Duration::from_millis(500)));
+        assert_eq!(parse_duration(" 30 s "), Some(Duration::from_secs(30)));
+        assert_eq!(parse_duration("5m"), Some(Duration::from_secs(300)));
+        assert_eq!(parse_duration("2h"), Some(Duration::from_secs(7200)));
+        assert_eq!(parse_duration("1d"), Some(Duration::from_secs(86400)));
+    }
+
+    #[test]
+    fn parse_duration_rejects_bad_values() {
+        for text in ["", "5", "0s", "5w", "m", "-5s"] {
+            assert_eq!(parse_duration(text), None, "{}", text);
+        }
+    }
+
+    #[test]
+    fn parse_duration_rejects_overflow() {
+        assert_eq!(parse_duration(&format!("{}m", u64::MAX)), None);
+        assert_eq!(parse_duration(&format!("{}h", u64::MAX / 60)), None);
+        assert_eq!(parse_duration(&format!("{}d", u64::MAX / 3600)), None);
+        assert!(parse_duration(&format!("{}s", u64::MAX)).is_some());
+    }
+}
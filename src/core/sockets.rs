@@ -1,46 +1,349 @@
 // taurifest/src/core/sockets.rs - ПОЛНАЯ ИСПРАВЛЕННАЯ ВЕРСИЯ
 
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use rhai::{Dynamic, Engine, Scope};
 use serde_json::Value;
-use tauri::{AppHandle, Emitter}; // <-- ИСПРАВЛЕНИЕ 1: Manager -> Emitter
 
-use crate::core::errors::AppError;
+use crate::core::errors::{ActionError, AppError};
+use crate::core::events::{emit_to_target, target_labels};
+use crate::core::manifest::{EmitTarget, PayloadConfig, SocketConfig};
 use crate::AppState;
 
-pub struct SocketManager;
+pub struct SocketManager {
+    /// Движок для условий `when` и выражений `payload`.
+    rhai_engine: Engine,
+    /// Отложенные уведомления сокетов с `debounceMs`: имя сокета -> последние данные.
+    pending: Mutex<HashMap<String, PendingNotification>>,
+    /// Счетчик для `PendingNotification::generation`.
+    next_generation: AtomicU64,
+}
+
+/// Последняя запись, о которой должен сообщить сокет с `debounceMs`.
+/// Если за время ожидания пришло несколько записей, хранится только последняя
+/// (в том числе ее `origin`).
+struct PendingNotification {
+    connector_name: String,
+    written: Value,
+    origin: Option<String>,
+    /// Номер записи. Отложенная задача отправляет событие, только если
+    /// за время ожидания не пришло записи новее.
+    generation: u64,
+}
+
+impl Default for SocketManager {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
 impl SocketManager {
     pub fn new() -> Self {
-        Self {}
+        let mut engine = Engine::new();
+        engine.set_max_operations(100_000);
+        Self { rhai_engine: engine, pending: Mutex::new(HashMap::new()), next_generation: AtomicU64::new(0) }
     }
 
+    /// Вызывается после успешной записи `written` в коннектор `connector_name`.
+    /// Отправляет события всех сокетов, которые следят за этим коннектором.
+    /// `origin` - метка окна, из которого пришло действие (если оно было).
+    ///
+    /// Данные к этому моменту уже записаны, поэтому ошибка сокета (например,
+    /// в `when` или `payload`) только логируется: она не должна прерывать
+    /// остальные `writes` роута и выдавать успешную запись за неудачу.
     pub async fn notify_on_write(
         &self,
         connector_name: &str,
+        written: &Value,
+        origin: Option<&str>,
         state: &AppState,
-        app_handle: &AppHandle,
-    ) -> Result<(), AppError> {
+    ) {
         // --- ИСПРАВЛЕНИЕ 2: Убираем `if let Some`, так как `sockets` - это HashMap, а не Option ---
         // `#[serde(default)]` гарантирует, что `sockets` всегда будет как минимум пустым HashMap.
         // Поэтому мы можем итерировать по нему напрямую.
-        for (_channel_name, socket_config) in &state.manifest.sockets {
-            if socket_config.watch == connector_name {
-                let event_name = &socket_config.emit.event;
-                let payload_source_name = &socket_config.emit.payload;
+        let manifest = state.manifest();
+        for (socket_name, socket_config) in &manifest.sockets {
+            if socket_config.watch != connector_name {
+                continue;
+            }
+
+            match socket_config.debounce_ms {
+                Some(debounce_ms) => self.schedule(socket_name, debounce_ms, connector_name, written, origin, state),
+                None => {
+                    if let Err(e) = self.emit_socket(socket_config, connector_name, written, origin, state).await {
+                        tracing::error!(socket = %socket_name, error = %e, "Socket emit failed");
+                    }
+                }
+            }
+        }
+    }
+
+    /// Откладывает уведомление сокета с `debounceMs`: событие уйдет, когда
+    /// записей не будет `debounce_ms`. Каждая запись заменяет отложенные данные
+    /// на свежие и заново отсчитывает время, поэтому при непрерывных записях
+    /// событие не отправляется, пока они не прекратятся.
+    fn schedule(
+        &self,
+        socket_name: &str,
+        debounce_ms: u64,
+        connector_name: &str,
+        written: &Value,
+        origin: Option<&str>,
+        state: &AppState,
+    ) {
+        let notification = PendingNotification {
+            connector_name: connector_name.to_string(),
+            written: written.clone(),
+            origin: origin.map(str::to_string),
+            generation: self.next_generation.fetch_add(1, Ordering::Relaxed),
+        };
+        let generation = notification.generation;
+        self.pending.lock().expect("SocketManager lock poisoned").insert(socket_name.to_string(), notification);
+
+        // Задачи предыдущих записей проснутся раньше и ничего не отправят.
+        let socket_name = socket_name.to_string();
+        let state = state.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(debounce_ms)).await;
+            if let Err(e) = state.socket_manager.flush(&socket_name, generation, &state).await {
+                tracing::error!(socket = %socket_name, error = %e, "Debounced emit failed");
+            }
+        });
+    }
+
+    /// Отправляет отложенное уведомление сокета, если после записи `generation`
+    /// новых записей не было.
+    async fn flush(&self, socket_name: &str, generation: u64, state: &AppState) -> Result<(), AppError> {
+        let notification = {
+            let mut pending = self.pending.lock().expect("SocketManager lock poisoned");
+            match pending.get(socket_name) {
+                Some(notification) if notification.generation == generation => pending.remove(socket_name),
+                _ => None,
+            }
+        };
+        let Some(notification) = notification else { return Ok(()) };
+
+        // Манифест мог быть перезагружен, пока мы ждали.
+        let manifest = state.manifest();
+        let Some(socket_config) = manifest.sockets.get(socket_name) else { return Ok(()) };
 
-                let payload = if let Some(connector) = state.connector_manager.get(payload_source_name) {
+        self.emit_socket(
+            socket_config,
+            &notification.connector_name,
+            &notification.written,
+            notification.origin.as_deref(),
+            state,
+        ).await
+    }
+
+    /// Вычисляет условие и полезную нагрузку одного сокета и отправляет событие.
+    async fn emit_socket(
+        &self,
+        socket_config: &SocketConfig,
+        connector_name: &str,
+        written: &Value,
+        origin: Option<&str>,
+        state: &AppState,
+    ) -> Result<(), AppError> {
+        // Действие пришло не из окна (например, из фоновой задачи) - адресата `origin` нет.
+        if socket_config.target == EmitTarget::Origin && origin.is_none() {
+            return Ok(());
+        }
+
+        let event_name = &socket_config.emit.event;
+
+        // Условие и выражение видят записанные данные как `data`,
+        // а имя коннектора - как `connector`.
+        let mut scope = Scope::new();
+        scope.push_constant("data", rhai::serde::to_dynamic(written).map_err(|e| ActionError::Rhai(e.to_string()))?);
+        scope.push_constant("connector", connector_name.to_string());
+
+        if let Some(condition) = &socket_config.when {
+            let should_emit = self.rhai_engine.eval_with_scope::<bool>(&mut scope, condition)
+                .map_err(|e| ActionError::Rhai(format!("in 'when' of socket '{}': {}", event_name, e)))?;
+            if !should_emit {
+                return Ok(());
+            }
+        }
+
+        let payload = match &socket_config.emit.payload {
+            Some(PayloadConfig::Connector(payload_source_name)) => {
+                if let Some(connector) = state.connector_manager.get(payload_source_name) {
                     connector.read().await?
                 } else {
                     Value::Null
-                };
-                
-                println!("[SocketManager] Emitting event '{}' due to write on connector '{}'", event_name, connector_name);
-                
-                // Теперь этот вызов корректен, так как трейт `Emitter` в области видимости
-                app_handle.emit(event_name, payload)
-                    .map_err(|e| AppError::Config(format!("Tauri event emit failed: {}", e)))?;
+                }
             }
-        }
+            Some(PayloadConfig::Expression { expr }) => {
+                let result = self.rhai_engine.eval_with_scope::<Dynamic>(&mut scope, expr)
+                    .map_err(|e| ActionError::Rhai(format!("in payload of socket '{}': {}", event_name, e)))?;
+                serde_json::to_value(result).unwrap_or(Value::Null)
+            }
+            None => Value::Null,
+        };
+
+        let sink = state.event_sink();
+        let target_labels = target_labels(sink.as_ref(), &socket_config.target, origin);
+
+        let payload = match &socket_config.emit.render {
+            Some(component_name) => {
+                let html = self.render(component_name, connector_name, written, &socket_config.emit.reads, state).await?;
+
+                // Окна получат этот HTML целиком, поэтому он становится
+                // базой для следующих патчей в каждом из них.
+                for window_label in &target_labels {
+                    state.renderer.remember(window_label, component_name, &html);
+                }
+
+                let mut rendered = serde_json::json!({ "component": component_name, "html": html });
+                if !payload.is_null() {
+                    rendered["data"] = payload;
+                }
+                rendered
+            }
+            None => payload,
+        };
+
+        tracing::debug!(event = %event_name, connector = connector_name, "Emitting socket event");
+
+        emit_to_target(sink.as_ref(), &socket_config.target, origin, event_name, payload)?;
 
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Рендерит компонент для сокета с `render`: в `data` лежат только что
+    /// записанные данные коннектора и данные из `reads`.
+    async fn render(
+        &self,
+        component_name: &str,
+        connector_name: &str,
+        written: &Value,
+        reads: &[String],
+        state: &AppState,
+    ) -> Result<String, AppError> {
+        let owner = format!("socket render '{}'", component_name);
+        let mut data = state.read_connectors(reads, &owner).await?;
+        if let Some(data_map) = data.as_object_mut() {
+            data_map.insert(connector_name.to_string(), written.clone());
+        }
+        let user = state.resolve_user().await?;
+
+        let render_context = serde_json::json!({
+            "data": data,
+            "user": user,
+            "globals": state.manifest().globals,
+        });
+
+        state.render(component_name, &render_context).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+
+    use serde_json::{json, Value};
+
+    use crate::core::errors::AppError;
+    use crate::core::test_support::TempApp;
+    use crate::{Engine, EventSink};
+
+    /// Запоминает отправленные события.
+    #[derive(Default)]
+    struct RecordingSink {
+        events: Mutex<Vec<(String, Value)>>,
+    }
+
+    impl RecordingSink {
+        fn events(&self) -> Vec<(String, Value)> {
+            self.events.lock().unwrap().clone()
+        }
+    }
+
+    impl EventSink for RecordingSink {
+        fn emit(&self, event: &str, payload: Value) -> Result<(), AppError> {
+            self.events.lock().unwrap().push((event.to_string(), payload));
+            Ok(())
+        }
+
+        fn emit_to(&self, _window_label: &str, event: &str, payload: Value) -> Result<(), AppError> {
+            self.emit(event, payload)
+        }
+
+        fn emit_except(&self, _excluded_label: &str, event: &str, payload: Value) -> Result<(), AppError> {
+            self.emit(event, payload)
+        }
+
+        fn window_labels(&self) -> Vec<String> {
+            Vec::new()
+        }
+    }
+
+    #[tokio::test]
+    async fn failing_socket_condition_does_not_abort_writes() {
+        let app = TempApp::new();
+        app.write("manifest.json", r#"{
+            "connectors": {
+                "todos": { "type": "in-memory", "initial_state": { "items": [] } },
+                "log": { "type": "in-memory", "initial_state": { "entries": [] } }
+            },
+            "sockets": {
+                "todosChanged": { "watch": "todos", "when": "data.missing.len() > 0", "emit": { "event": "todos:changed" } }
+            },
+            "routes": {
+                "add": {
+                    "type": "action",
+                    "reads": ["todos", "log"],
+                    "writes": ["todos", "log"],
+                    "steps": [
+                        { "set": "data.todos.items", "to": "[1]" },
+                        { "set": "data.log.entries", "to": "[\"added\"]" }
+                    ]
+                }
+            }
+        }"#);
+        let engine = Engine::load_in_memory(app.path(), None).unwrap();
+
+        engine.run_route("add", json!({})).await.unwrap();
+
+        // `todos` записан первым, его сокет упал - но и `log` все равно записан.
+        let read = |name: &str| engine.connector_manager.get(name).unwrap().clone();
+        assert_eq!(read("todos").read().await.unwrap(), json!({ "items": [1] }));
+        assert_eq!(read("log").read().await.unwrap(), json!({ "entries": ["added"] }));
+    }
+
+    #[tokio::test]
+    async fn debounced_socket_waits_for_writes_to_stop() {
+        let app = TempApp::new();
+        app.write("manifest.json", r#"{
+            "connectors": { "todos": { "type": "in-memory", "initial_state": { "items": [] } } },
+            "sockets": {
+                "todosChanged": {
+                    "watch": "todos", "debounceMs": 200,
+                    "emit": { "event": "todos:changed", "payload": { "expr": "data.items.len()" } }
+                }
+            },
+            "routes": {
+                "add": { "type": "action", "reads": ["todos"], "writes": ["todos"], "steps": [{ "set": "data.todos.items", "to": "data.todos.items + [1]" }] }
+            }
+        }"#);
+        let engine = Engine::load_in_memory(app.path(), None).unwrap();
+        let sink = Arc::new(RecordingSink::default());
+        engine.set_event_sink(sink.clone());
+
+        // Записи каждые 100 мс: каждая откладывает событие еще на 200 мс.
+        for _ in 0..3 {
+            engine.run_route("add", json!({})).await.unwrap();
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+        // 300 мс после первой записи, но только 100 мс после последней.
+        assert!(sink.events().is_empty(), "{:?}", sink.events());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert_eq!(sink.events(), vec![("todos:changed".to_string(), json!(3))]);
+    }
+}
@@ -0,0 +1,46 @@
+// taurifest/src/core/test_support.rs
+//
+// Помощники модульных тестов, которым нужна настоящая папка приложения.
+
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Временная папка приложения. Удаляется вместе с содержимым, когда значение
+/// выходит из области видимости.
+pub(crate) struct TempApp {
+    path: PathBuf,
+}
+
+impl TempApp {
+    pub(crate) fn new() -> Self {
+        static NEXT: AtomicUsize = AtomicUsize::new(0);
+        let path = std::env::temp_dir().join(format!(
+            "taurifest-test-{}-{}",
+            std::process::id(),
+            NEXT.fetch_add(1, Ordering::SeqCst)
+        ));
+        let _ = std::fs::remove_dir_all(&path);
+        std::fs::create_dir_all(&path).expect("Failed to create temp app directory");
+        Self { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Записывает файл по пути относительно папки приложения, создавая промежуточные папки.
+    pub(crate) fn write(&self, relative: &str, content: &str) -> &Self {
+        let file = self.path.join(relative);
+        if let Some(parent) = file.parent() {
+            std::fs::create_dir_all(parent).expect("Failed to create temp app subdirectory");
+        }
+        std::fs::write(&file, content).expect("Failed to write temp app file");
+        self
+    }
+}
+
+impl Drop for TempApp {
+    fn drop(&mut self) {
+        let _ = std::fs::remove_dir_all(&self.path);
+    }
+}
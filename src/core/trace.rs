@@ -0,0 +1,84 @@
+// taurifest/src/core/trace.rs
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Событие, с которым отправляется трассировка роутов, запущенных не из
+/// `run_action` (слушатели, расписание, хуки): `{ route, steps }`.
+pub const TRACE_EVENT: &str = "taurifest://trace";
+
+/// Запись о выполнении одного шага в режиме трассировки.
+#[derive(Debug, Clone, Serialize)]
+pub struct StepTrace {
+    /// Тип шага: `set`, `if`, `action:run`, `emit`.
+    pub kind: &'static str,
+    /// Путь `set`, имя события `emit` или имя под-роута `action:run`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+    /// Выражение шага (`to`, `if`, `payload`).
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expression: Option<String>,
+    /// Результат выражения.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Выбранная ветка условия: `then` или `else`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub branch: Option<&'static str>,
+    #[serde(rename = "durationUs")]
+    pub duration_us: u64,
+    /// Что изменилось в `context` и `data` после шага.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub changes: Vec<ContextChange>,
+    /// Шаги под-роута для `action:run`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub steps: Vec<StepTrace>,
+}
+
+impl StepTrace {
+    pub fn new(kind: &'static str, target: Option<&str>, expression: Option<&str>) -> Self {
+        Self {
+            kind,
+            target: target.map(str::to_string),
+            expression: expression.map(str::to_string),
+            result: None,
+            branch: None,
+            duration_us: 0,
+            changes: Vec::new(),
+            steps: Vec::new(),
+        }
+    }
+}
+
+/// Одно изменение значения по пути вида `context.total` или `data.todos.items`.
+/// Отсутствующее значение обозначается `null`.
+#[derive(Debug, Clone, Serialize)]
+pub struct ContextChange {
+    pub path: String,
+    pub before: Value,
+    pub after: Value,
+}
+
+/// Сравнивает два JSON-значения. Объекты сравниваются по ключам рекурсивно,
+/// все остальное (в том числе массивы) - целиком. `path` - путь к значениям
+/// (пустой для корня).
+pub fn diff_values(path: &str, before: &Value, after: &Value, changes: &mut Vec<ContextChange>) {
+    match (before, after) {
+        (Value::Object(old), Value::Object(new)) => {
+            let child_path = |key: &str| if path.is_empty() { key.to_string() } else { format!("{}.{}", path, key) };
+            for (key, old_value) in old {
+                diff_values(&child_path(key), old_value, new.get(key).unwrap_or(&Value::Null), changes);
+            }
+            for (key, new_value) in new {
+                if !old.contains_key(key) {
+                    diff_values(&child_path(key), &Value::Null, new_value, changes);
+                }
+            }
+        }
+        _ if before != after => changes.push(ContextChange {
+            path: path.to_string(),
+            before: before.clone(),
+            after: after.clone(),
+        }),
+        _ => {}
+    }
+}
@@ -0,0 +1,185 @@
+// taurifest/src/core/validation.rs
+
+use rhai::Engine;
+use scraper::{Html, Selector};
+
+use crate::core::asset_loader::AssetLoader;
+use crate::core::connectors::CONNECTOR_TYPES;
+use crate::core::manifest::{Manifest, PayloadConfig, Step};
+use crate::core::scheduler;
+
+/// Проверяет загруженное приложение глубже, чем разбор манифеста:
+/// типы коннекторов, ссылки на коннекторы, компоненты и роуты, синтаксис всех Rhai-выражений
+/// (шаги, сокеты, `atom-if` в шаблонах), расписания и профили.
+///
+/// Возвращает список найденных проблем в виде `где: что не так`,
+/// отсортированный для стабильного вывода. Пустой список - все в порядке.
+pub fn validate(manifest: &Manifest, asset_loader: &AssetLoader) -> Vec<String> {
+    let mut checker = Checker { manifest, rhai: Engine::new(), problems: Vec::new() };
+
+    // `load_in_memory` подменяет все коннекторы хранилищем в памяти,
+    // поэтому неизвестный тип без этой проверки всплыл бы только при запуске.
+    for (name, connector) in &manifest.connectors {
+        checker.connector_type(&format!("connectors.{}", name), &connector.connector_type);
+    }
+
+    for (name, route) in &manifest.routes {
+        let place = format!("routes.{}", name);
+        for connector in route.reads.iter().chain(&route.writes) {
+            checker.connector(&place, connector);
+        }
+        if let Some(update) = &route.update {
+            for (component, _) in update.components() {
+                checker.component(&place, component);
+            }
+        }
+        checker.steps(&format!("{}.steps", place), &route.steps);
+    }
+
+    for (name, socket) in &manifest.sockets {
+        let place = format!("sockets.{}", name);
+        checker.connector(&place, &socket.watch);
+        if let Some(condition) = &socket.when {
+            checker.expression(&format!("{}.when", place), condition);
+        }
+        match &socket.emit.payload {
+            Some(PayloadConfig::Connector(connector)) => checker.connector(&format!("{}.emit.payload", place), connector),
+            Some(PayloadConfig::Expression { expr }) => checker.expression(&format!("{}.emit.payload", place), expr),
+            None => {}
+        }
+        if let Some(component) = &socket.emit.render {
+            checker.component(&format!("{}.emit.render", place), component);
+        }
+        for connector in &socket.emit.reads {
+            checker.connector(&format!("{}.emit.reads", place), connector);
+        }
+    }
+
+    for (name, page) in &manifest.pages {
+        let place = format!("pages.{}", name);
+        for component in page.layout.iter().chain(&page.components) {
+            checker.component(&place, component);
+        }
+        for connector in &page.reads {
+            checker.connector(&place, connector);
+        }
+    }
+
+    for (event, route) in &manifest.listeners {
+        checker.route(&format!("listeners.{}", event), route);
+    }
+
+    for (name, job) in &manifest.schedule {
+        let place = format!("schedule.{}", name);
+        checker.route(&place, &job.route);
+        if let Err(e) = scheduler::check(name, job) {
+            checker.problems.push(format!("{}: {}", place, e));
+        }
+    }
+
+    let hooks = [
+        ("hooks.onStart", &manifest.hooks.on_start),
+        ("hooks.onExit", &manifest.hooks.on_exit),
+        ("hooks.onWindowCreated", &manifest.hooks.on_window_created),
+    ];
+    for (place, route) in hooks {
+        if let Some(route) = route {
+            checker.route(place, route);
+        }
+    }
+
+    // Выбранный профиль проверяется при загрузке, остальные - здесь.
+    for (name, profile) in &manifest.profiles {
+        for (connector, overrides) in &profile.connectors {
+            let place = format!("profiles.{}.connectors", name);
+            checker.connector(&place, connector);
+            // `${env:...}` в невыбранном профиле не подставляется - тип станет известен при запуске.
+            if let Some(connector_type) = overrides.connector_type.as_ref().filter(|t| !t.contains("${env:")) {
+                checker.connector_type(&format!("{}.{}", place, connector), connector_type);
+            }
+        }
+    }
+
+    let atom_if = Selector::parse("[atom-if]").expect("static selector");
+    for name in manifest.components.keys() {
+        let Some(asset) = asset_loader.get_component(name) else { continue };
+        let document = Html::parse_fragment(&asset.template);
+        for element in document.select(&atom_if) {
+            if let Some(condition) = element.value().attr("atom-if") {
+                checker.expression(&format!("components.{} atom-if", name), condition);
+            }
+        }
+    }
+
+    let mut problems = checker.problems;
+    problems.sort();
+    problems
+}
+
+struct Checker<'a> {
+    manifest: &'a Manifest,
+    rhai: Engine,
+    problems: Vec<String>,
+}
+
+impl Checker<'_> {
+    fn connector(&mut self, place: &str, name: &str) {
+        if !self.manifest.connectors.contains_key(name) {
+            self.problems.push(format!("{}: connector '{}' is not defined", place, name));
+        }
+    }
+
+    fn connector_type(&mut self, place: &str, connector_type: &str) {
+        if !CONNECTOR_TYPES.contains(&connector_type) {
+            self.problems.push(format!(
+                "{}: unsupported connector type '{}' (expected one of: {})",
+                place, connector_type, CONNECTOR_TYPES.join(", "),
+            ));
+        }
+    }
+
+    fn component(&mut self, place: &str, name: &str) {
+        if !self.manifest.components.contains_key(name) {
+            self.problems.push(format!("{}: component '{}' is not defined", place, name));
+        }
+    }
+
+    fn route(&mut self, place: &str, name: &str) {
+        if !self.manifest.routes.contains_key(name) {
+            self.problems.push(format!("{}: route '{}' is not defined", place, name));
+        }
+    }
+
+    /// Проверяет только синтаксис: переменные появляются в области видимости
+    /// лишь во время выполнения.
+    fn expression(&mut self, place: &str, expr: &str) {
+        if let Err(e) = self.rhai.compile(expr) {
+            self.problems.push(format!("{}: invalid expression '{}': {}", place, expr, e));
+        }
+    }
+
+    fn steps(&mut self, place: &str, steps: &[Step]) {
+        for (index, step) in steps.iter().enumerate() {
+            let place = format!("{}[{}]", place, index);
+            match step {
+                Step::ActionRun(action_run) => self.route(&format!("{}.action:run", place), &action_run.name),
+                Step::Emit(emit) => {
+                    if let Some(payload) = &emit.payload {
+                        self.expression(&format!("{}.emit.payload", place), payload);
+                    }
+                }
+                Step::Set(set) => {
+                    self.expression(&format!("{}.to", place), &set.to);
+                    if !set.set.starts_with("context.") && !set.set.starts_with("data.") {
+                        self.problems.push(format!("{}: only 'context.*' and 'data.*' can be changed via 'set', got '{}'", place, set.set));
+                    }
+                }
+                Step::If(branch) => {
+                    self.expression(&format!("{}.if", place), &branch.condition);
+                    self.steps(&format!("{}.then", place), &branch.then);
+                    self.steps(&format!("{}.else", place), &branch.an_else);
+                }
+            }
+        }
+    }
+}
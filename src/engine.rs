@@ -0,0 +1,247 @@
+// taurifest/src/engine.rs
+
+use std::path::Path;
+use std::sync::{Arc, RwLock};
+
+use serde_json::Value;
+use tracing::Instrument;
+
+use crate::core::action_engine::ActionEngine;
+use crate::core::asset_loader::AssetLoader;
+use crate::core::connectors::ConnectorManager;
+use crate::core::errors::AppError;
+use crate::core::events::{EventSink, NullEventSink};
+use crate::core::manifest::{Manifest, UpdateConfig};
+use crate::core::pipeline;
+use crate::core::profiles;
+use crate::core::renderer::Renderer;
+use crate::core::sockets::SocketManager;
+use crate::core::trace::StepTrace;
+use crate::core::validation;
+
+/// `Engine` - движок Taurifest без привязки к окнам: загружает папку приложения
+/// и выполняет роуты и рендер. Им пользуются команды Tauri, а также тесты и
+/// утилиты командной строки, которым webview не нужен.
+///
+/// Все сервисы хранятся в `Arc`, поэтому клонирование дешевое: клон работает
+/// с тем же манифестом, коннекторами и получателем событий.
+#[derive(Clone)]
+pub struct Engine {
+    loaded: Arc<RwLock<LoadedApp>>,
+    event_sink: Arc<RwLock<Arc<dyn EventSink>>>,
+    pub connector_manager: Arc<ConnectorManager>,
+    pub action_engine: Arc<ActionEngine>,
+    pub renderer: Arc<Renderer>,
+    pub socket_manager: Arc<SocketManager>,
+    /// Режим трассировки: записывать каждый шаг роутов (см. `Builder::trace`).
+    pub trace: bool,
+    /// Выбранный профиль манифеста; с ним же манифест перечитывается при горячей перезагрузке.
+    profile: Option<String>,
+}
+
+/// Части состояния, которые читаются с диска и могут быть подменены
+/// при горячей перезагрузке. Хранятся под одной блокировкой, чтобы манифест
+/// и ассеты всегда менялись вместе.
+struct LoadedApp {
+    manifest: Arc<Manifest>,
+    asset_loader: Arc<AssetLoader>,
+}
+
+/// Результат выполнения роута через `Engine::run_route`.
+#[derive(Debug, Clone)]
+pub struct RouteResult {
+    /// Данные коннекторов после выполнения шагов (уже записанные в `writes`).
+    pub data: Value,
+    /// Итоговое значение временного `context`.
+    pub context: Value,
+    /// Секция `update` роута, по которой отрисованы компоненты.
+    pub update: Option<UpdateConfig>,
+    /// Компоненты из `update` в порядке их объявления.
+    pub rendered: Vec<RenderedComponent>,
+    /// Записи о шагах, если включена трассировка.
+    pub trace: Option<Vec<StepTrace>>,
+}
+
+/// Компонент, отрисованный после выполнения роута.
+#[derive(Debug, Clone)]
+pub struct RenderedComponent {
+    pub component: String,
+    /// CSS-селектор из `update`, если он задан.
+    pub target: Option<String>,
+    pub html: String,
+}
+
+impl Engine {
+    /// Загружает приложение из папки `app_path`: манифест (`manifest.json`,
+    /// `.yaml` или `.toml`), ассеты компонентов и коннекторы (данные хранятся в `app_path/data`).
+    /// События по умолчанию отбрасываются (`NullEventSink`).
+    ///
+    /// `profile` - профиль из секции `profiles`; переменная `TAURIFEST_PROFILE`
+    /// важнее него (см. `core::profiles::select`).
+    pub fn load(app_path: impl AsRef<Path>, profile: Option<&str>) -> Result<Self, AppError> {
+        let app_path = app_path.as_ref();
+        let profile = profiles::select(profile);
+        let manifest = Manifest::load(app_path, profile.as_deref())?;
+
+        let asset_loader = AssetLoader::new(app_path, &manifest)
+            .map_err(|e| AppError::Config(format!("Failed to load application assets: {}", e)))?;
+        let connector_manager = ConnectorManager::new(&manifest.connectors, app_path.join("data"))?;
+
+        Ok(Self::from_parts(manifest, asset_loader, connector_manager, profile))
+    }
+
+    /// То же, что `load`, но все коннекторы хранят данные в памяти
+    /// (начиная с `initial_state`), а файлы в `app_path/data` не используются.
+    pub fn load_in_memory(app_path: impl AsRef<Path>, profile: Option<&str>) -> Result<Self, AppError> {
+        let app_path = app_path.as_ref();
+        let profile = profiles::select(profile);
+        let manifest = Manifest::load(app_path, profile.as_deref())?;
+
+        let asset_loader = AssetLoader::new(app_path, &manifest)
+            .map_err(|e| AppError::Config(format!("Failed to load application assets: {}", e)))?;
+        let connector_manager = ConnectorManager::in_memory(&manifest.connectors);
+
+        Ok(Self::from_parts(manifest, asset_loader, connector_manager, profile))
+    }
+
+    fn from_parts(manifest: Manifest, asset_loader: AssetLoader, connector_manager: ConnectorManager, profile: Option<String>) -> Self {
+        Self {
+            profile,
+            loaded: Arc::new(RwLock::new(LoadedApp {
+                manifest: Arc::new(manifest),
+                asset_loader: Arc::new(asset_loader),
+            })),
+            event_sink: Arc::new(RwLock::new(Arc::new(NullEventSink))),
+            connector_manager: Arc::new(connector_manager),
+            action_engine: Arc::new(ActionEngine::new()),
+            renderer: Arc::new(Renderer::new()),
+            socket_manager: Arc::new(SocketManager::new()),
+            trace: false,
+        }
+    }
+
+    /// Включает трассировку шагов (см. `RouteResult::trace`).
+    pub fn with_trace(mut self, enabled: bool) -> Self {
+        self.trace = enabled;
+        self
+    }
+
+    /// Профиль, с которым загружен манифест.
+    pub fn profile(&self) -> Option<&str> {
+        self.profile.as_deref()
+    }
+
+    /// Подменяет получателя событий. Действует на все клоны движка.
+    pub fn set_event_sink(&self, sink: Arc<dyn EventSink>) {
+        *self.event_sink.write().expect("Engine lock poisoned") = sink;
+    }
+
+    /// Возвращает текущего получателя событий.
+    pub fn event_sink(&self) -> Arc<dyn EventSink> {
+        self.event_sink.read().expect("Engine lock poisoned").clone()
+    }
+
+    /// Возвращает текущий снимок манифеста.
+    /// Снимок остается валидным, даже если во время выполнения роута
+    /// произойдет горячая перезагрузка.
+    pub fn manifest(&self) -> Arc<Manifest> {
+        self.loaded.read().expect("Engine lock poisoned").manifest.clone()
+    }
+
+    /// Возвращает текущий снимок загруженных ассетов.
+    pub fn asset_loader(&self) -> Arc<AssetLoader> {
+        self.loaded.read().expect("Engine lock poisoned").asset_loader.clone()
+    }
+
+    /// Выполняет роут `name` с телом запроса `body` и отрисовывает компоненты из его `update`.
+    pub async fn run_route(&self, name: &str, body: Value) -> Result<RouteResult, AppError> {
+        self.run_route_from(name, body, None).await
+    }
+
+    /// То же, что `run_route`, но от имени окна `origin` (для адресатов `origin`/`others`).
+    pub async fn run_route_from(&self, name: &str, body: Value, origin: Option<&str>) -> Result<RouteResult, AppError> {
+        // Берем снимок манифеста: горячая перезагрузка не должна подменить его посреди выполнения.
+        let manifest = self.manifest();
+        let route = manifest.routes.get(name)
+            .ok_or_else(|| AppError::Config(format!("Route '{}' not found in manifest", name)))?;
+
+        let final_context = pipeline::execute_route(self, name, route, body, origin).await?;
+
+        // Все компоненты рендерятся из одного и того же финального контекста.
+        let mut rendered = Vec::new();
+        if let Some(update) = &route.update {
+            let render_context = serde_json::json!({
+                "data": final_context.data,
+                "user": final_context.user,
+                "globals": manifest.globals,
+            });
+            for (component, target) in update.components() {
+                rendered.push(RenderedComponent {
+                    component: component.to_string(),
+                    target: target.map(str::to_string),
+                    html: self.render(component, &render_context).await?,
+                });
+            }
+        }
+
+        Ok(RouteResult {
+            data: final_context.data,
+            context: final_context.temp,
+            update: route.update.clone(),
+            rendered,
+            trace: final_context.trace,
+        })
+    }
+
+    /// Рендерит компонент с готовым контекстом (`{ data, user, globals, ... }`).
+    pub async fn render(&self, component: &str, context: &Value) -> Result<String, AppError> {
+        Ok(self.renderer.render_component(&self.asset_loader(), component, context).await?)
+    }
+
+    /// Проверяет ссылки и выражения в манифесте и шаблонах (см. `core::validation`).
+    /// Пустой список означает, что проблем не найдено.
+    pub fn validate(&self) -> Vec<String> {
+        validation::validate(&self.manifest(), &self.asset_loader())
+    }
+
+    /// Читает данные всех коннекторов из списка `reads` в объект `{ имя: данные }`.
+    /// `owner` используется только в тексте ошибки (например, "action 'addTodo'").
+    pub async fn read_connectors(&self, reads: &[String], owner: &str) -> Result<Value, AppError> {
+        let mut data_map = serde_json::Map::new();
+        for connector_name in reads {
+            if let Some(connector) = self.connector_manager.get(connector_name) {
+                let connector_data = connector.read()
+                    .instrument(tracing::debug_span!("connector.read", connector = connector_name.as_str()))
+                    .await?;
+                data_map.insert(connector_name.clone(), connector_data);
+            } else {
+                return Err(AppError::Config(format!("Connector '{}' in reads for {} is not defined.", connector_name, owner)));
+            }
+        }
+        Ok(Value::Object(data_map))
+    }
+
+    /// Определяет текущего пользователя для контекста.
+    /// Единая точка для роутов, рендера компонентов и страниц: когда появится
+    /// сессия по `auth`, проверка добавится здесь и будет действовать везде.
+    pub async fn resolve_user(&self) -> Result<Value, AppError> {
+        Ok(Value::Null) // `user` пока `Null`
+    }
+
+    /// Атомарно подменяет манифест и ассеты (используется горячей перезагрузкой).
+    /// Сначала приводит коннекторы к новому манифесту (см. `ConnectorManager::reload`):
+    /// если какой-то не создается, ничего не меняется. Коннекторы с прежними
+    /// настройками не пересоздаются, их данные остаются нетронутыми.
+    pub(crate) fn replace_loaded(&self, manifest: Manifest, asset_loader: AssetLoader) -> Result<(), AppError> {
+        self.connector_manager.reload(&manifest.connectors)?;
+
+        let mut loaded = self.loaded.write().expect("Engine lock poisoned");
+        *loaded = LoadedApp {
+            manifest: Arc::new(manifest),
+            asset_loader: Arc::new(asset_loader),
+        };
+        // Шаблоны могли измениться: окна перерисуются целиком, патчи считать не от чего.
+        self.renderer.forget_all();
+        Ok(())
+    }
+}
@@ -6,44 +6,47 @@ use std::sync::Arc;
 // Объявляем наши основные модули.
 pub mod core;
 mod commands;
+mod engine;
+pub mod testing;
 
 // Импортируем все необходимые структуры из наших модулей.
-use crate::core::manifest::Manifest;
-use crate::core::connectors::ConnectorManager;
-use crate::core::action_engine::ActionEngine;
-use crate::core::renderer::Renderer;
-use crate::core::asset_loader::AssetLoader;
-use crate::core::sockets::SocketManager;
-use crate::commands::run_action;
+use crate::core::events::TauriEventSink;
+use crate::core::hooks;
+use crate::core::hot_reload;
+use crate::core::listeners;
+use crate::core::logging;
+use crate::core::scheduler;
+use crate::core::pages;
+use crate::commands::{render_component, run_action};
 
 // Делаем `Builder` публичным, чтобы пользователи могли его импортировать
 // из нашего крейта (`use taurifest::Builder;`).
 pub use self::builder::Builder;
+// Движок без Tauri-окон - для тестов и утилит командной строки.
+pub use self::engine::{Engine, RenderedComponent, RouteResult};
+pub use crate::core::events::EventSink;
 
 /// `AppState` - это центральное хранилище состояния нашего приложения.
-/// Tauri будет владеть этим состоянием и предоставлять к нему безопасный доступ
-/// из асинхронных команд. Все "сервисы" (менеджеры, движки) хранятся здесь.
-/// Мы используем `Arc` (Atomic Reference Counting) для безопасного совместного
-/// владения этими сервисами из разных потоков, что является требованием Tauri.
-pub struct AppState {
-    pub manifest: Manifest,
-    pub connector_manager: Arc<ConnectorManager>,
-    pub action_engine: Arc<ActionEngine>,
-    pub renderer: Arc<Renderer>,
-    pub asset_loader: Arc<AssetLoader>,
-    pub socket_manager: Arc<SocketManager>,
-}
+/// Tauri владеет им и предоставляет к нему доступ из асинхронных команд.
+/// Само состояние - это `Engine`: команды лишь передают ему вызовы из окон.
+pub type AppState = Engine;
 
 /// Внутренний модуль `builder` для инкапсуляции логики создания движка.
 mod builder {
     // Импортируем все из родительского модуля (`lib.rs`), чтобы иметь доступ
     // к `AppState` и другим необходимым структурам.
     use super::*;
+    use tauri::Manager;
 
     /// `Builder` - это основной способ инициализации движка `Taurifest`.
     /// Он использует паттерн "Строитель" для удобной и понятной настройки.
     pub struct Builder {
         app_path: PathBuf,
+        hot_reload: bool,
+        inject_runtime: bool,
+        trace: bool,
+        log_to_file: bool,
+        profile: Option<String>,
     }
 
     impl Builder {
@@ -52,63 +55,169 @@ mod builder {
         /// # Arguments
         ///
         /// * `app_path` - Путь к корневой папке приложения пользователя (например, "app" или "ui"),
-        ///   внутри которой находится `manifest.json`, а также папки `components`, `actions` и `data`.
+        ///   внутри которой находится манифест (`manifest.json`, `manifest.yaml` или `manifest.toml`),
+        ///   а также папки `components`, `actions` и `data`.
         pub fn new(app_path: impl Into<PathBuf>) -> Self {
-            Self { app_path: app_path.into() }
+            Self {
+                app_path: app_path.into(),
+                hot_reload: false,
+                inject_runtime: false,
+                trace: false,
+                log_to_file: false,
+                profile: None,
+            }
+        }
+
+        /// Включает режим разработки с горячей перезагрузкой.
+        ///
+        /// Движок следит за манифестом, файлами из его `include` и папкой `components`, при изменениях
+        /// перечитывает манифест и ассеты, подменяет их в `AppState` (новые и измененные
+        /// коннекторы создаются заново, прочие сохраняют данные) и отправляет
+        /// во фронтенд событие `taurifest://reload`. Клиентский runtime подписан на него:
+        /// страницы из секции `pages` перезагружаются, на собственных страницах
+        /// перерисовываются контейнеры `[atom-component]`.
+        pub fn hot_reload(mut self, enabled: bool) -> Self {
+            self.hot_reload = enabled;
+            self
+        }
+
+        /// Внедряет клиентский runtime (`atom-action`, `atom-target` и т.д.) в каждое окно
+        /// приложения, в том числе в страницы, написанные вручную.
+        /// Страницы из секции `pages` получают runtime и без этой опции.
+        pub fn inject_runtime(mut self, enabled: bool) -> Self {
+            self.inject_runtime = enabled;
+            self
+        }
+
+        /// Включает трассировку выполнения роутов (для отладки).
+        ///
+        /// Для каждого шага записываются тип, выражение, результат, выбранная
+        /// ветка, длительность и изменения `context`/`data`. `run_action`
+        /// возвращает записи в поле `trace` ответа, а роуты, запущенные
+        /// событиями, расписанием или хуками, отправляют их событием `taurifest://trace`.
+        pub fn trace(mut self, enabled: bool) -> Self {
+            self.trace = enabled;
+            self
+        }
+
+        /// Пишет логи движка в JSON-файл `taurifest.log` в папке данных приложения.
+        ///
+        /// Движок логирует через `tracing`: спаны `route` (имя, `reads`/`writes`,
+        /// длительность, вид ошибки), `step`, `connector.read`/`connector.write`
+        /// и `render`. Без этой опции логи получает подписчик, установленный
+        /// самим приложением, если он есть.
+        pub fn log_to_file(mut self, enabled: bool) -> Self {
+            self.log_to_file = enabled;
+            self
+        }
+
+        /// Выбирает профиль из секции `profiles` манифеста (`dev`, `prod`, `test`, ...):
+        /// он переопределяет `globals` и настройки коннекторов.
+        ///
+        /// Переменная окружения `TAURIFEST_PROFILE`, если задана, важнее этого
+        /// значения - так собранное приложение можно запустить с другим профилем.
+        pub fn profile(mut self, name: impl Into<String>) -> Self {
+            self.profile = Some(name.into());
+            self
         }
 
         /// Финальный метод, который выполняет всю работу по настройке:
-        /// 1. Читает и парсит `manifest.json`.
-        /// 2. Инициализирует все сервисы.
-        /// 3. Собирает их в `AppState`.
+        /// 1. Читает и парсит манифест.
+        /// 2. Загружает ассеты и коннекторы и собирает из них `Engine`.
+        /// 3. Передает движок под управление Tauri как `AppState`.
         /// 4. Возвращает `tauri::Builder`, готовый к запуску, с уже настроенным состоянием и командами.
         pub fn build(self) -> tauri::Builder<tauri::Wry> {
-            // --- Шаг 1: Загрузка и парсинг манифеста ---
-            let manifest_path = self.app_path.join("manifest.json");
-            let manifest_content = std::fs::read_to_string(&manifest_path)
-                .unwrap_or_else(|error| panic!("FATAL: Failed to read manifest.json from {:?}. Error: {}", manifest_path, error));
-            let manifest: Manifest = serde_json::from_str(&manifest_content)
-                .unwrap_or_else(|error| panic!("FATAL: Failed to parse manifest.json. Error: {}", error));
-
-            // --- Шаг 2: Инициализация всех сервисов движка ---
-
-            // Загрузчик ассетов (HTML шаблонов, CSS файлов)
-            let asset_loader = AssetLoader::new(&self.app_path, &manifest)
-                .expect("FATAL: Failed to load application assets");
-
-            // Менеджер коннекторов (баз данных)
-            let data_path = self.app_path.join("data");
-            let connector_manager = ConnectorManager::new(&manifest.connectors, data_path)
-                .expect("FATAL: Failed to initialize connectors");
-            
-            // Движок выполнения логики `steps`
-            let action_engine = ActionEngine::new();
-            
-            // Рендерер HTML
-            let renderer = Renderer::new();
-
-            // Менеджер real-time событий
-            let socket_manager = SocketManager::new();
-
-            // --- Шаг 3: Сборка глобального состояния `AppState` ---
-            // Оборачиваем все сервисы в `Arc`, чтобы Tauri мог безопасно
-            // передавать ссылки на них между потоками.
-            let state = AppState {
-                manifest,
-                connector_manager: Arc::new(connector_manager),
-                action_engine: Arc::new(action_engine),
-                renderer: Arc::new(renderer),
-                asset_loader: Arc::new(asset_loader),
-                socket_manager: Arc::new(socket_manager),
-            };
+            // --- Шаги 1-3: Загрузка манифеста, ассетов и коннекторов, сборка движка ---
+            let state = Engine::load(&self.app_path, self.profile.as_deref())
+                .unwrap_or_else(|error| panic!("FATAL: {}", error))
+                .with_trace(self.trace);
 
             // --- Шаг 4: Конфигурирование и возврат строителя Tauri ---
             tauri::Builder::default()
                 .manage(state) // Передаем наше состояние под управление Tauri
+                .plugin(self.plugin())
                 .invoke_handler(tauri::generate_handler![
                     run_action,
+                    render_component,
                     // Здесь будут регистрироваться другие команды
                 ])
         }
+
+        /// Собирает внутренний плагин движка. Все, что требует живого `AppHandle`
+        /// (наблюдатели, фоновые задачи), запускается в его `setup`. Плагин, а не
+        /// `tauri::Builder::setup`, используется для того, чтобы пользователь мог
+        /// свободно добавить собственный `setup` к возвращенному строителю.
+        fn plugin(&self) -> tauri::plugin::TauriPlugin<tauri::Wry> {
+            let app_path = self.app_path.clone();
+            let hot_reload = self.hot_reload;
+            let log_to_file = self.log_to_file;
+
+            let mut plugin = tauri::plugin::Builder::new("taurifest");
+            if self.inject_runtime {
+                plugin = plugin.js_init_script(pages::RUNTIME_SCRIPT.to_string());
+            }
+
+            plugin
+                .setup(move |app_handle, _api| {
+                    // Теперь события движка можно отправлять в окна.
+                    app_handle.state::<AppState>().set_event_sink(Arc::new(TauriEventSink::new(app_handle.clone())));
+                    if log_to_file {
+                        logging::install_file_subscriber(app_handle)?;
+                    }
+                    if hot_reload {
+                        hot_reload::watch(app_path, app_handle.clone())?;
+                    }
+                    hooks::on_start(app_handle)?;
+                    listeners::register(app_handle);
+                    scheduler::start(app_handle)?;
+                    Ok(())
+                })
+                .on_window_ready(hooks::on_window_created)
+                // Новая страница в окне - новый DOM: прошлые рендеры для патчей больше не годятся.
+                .on_page_load(|webview, payload| {
+                    if payload.event() == tauri::webview::PageLoadEvent::Started {
+                        webview.state::<AppState>().renderer.forget_window(webview.label());
+                    }
+                })
+                .on_event(|app_handle, event| {
+                    if let tauri::RunEvent::Exit = event {
+                        hooks::on_exit(app_handle);
+                    }
+                })
+                // Страницы из секции `pages` и сам runtime отдаются по протоколу `taurifest://`
+                // (на Windows и Android - `http://taurifest.localhost/`).
+                .register_asynchronous_uri_scheme_protocol("taurifest", |ctx, request, responder| {
+                    if request.uri().path() == pages::RUNTIME_PATH {
+                        let response = tauri::http::Response::builder()
+                            .header("Content-Type", "text/javascript; charset=utf-8")
+                            .body(pages::RUNTIME_SCRIPT.as_bytes().to_vec());
+                        if let Ok(response) = response {
+                            responder.respond(response);
+                        }
+                        return;
+                    }
+
+                    let app_handle = ctx.app_handle().clone();
+                    let window_label = ctx.webview_label().to_string();
+                    let page_name = pages::page_name_from_path(request.uri().path()).to_string();
+                    tauri::async_runtime::spawn(async move {
+                        let state = app_handle.state::<AppState>();
+                        let response = match pages::render_page(&state, &page_name, &window_label).await {
+                            Ok(html) => tauri::http::Response::builder()
+                                .header("Content-Type", "text/html; charset=utf-8")
+                                .body(html.into_bytes()),
+                            Err(error) => tauri::http::Response::builder()
+                                .status(404)
+                                .header("Content-Type", "text/plain; charset=utf-8")
+                                .body(error.to_string().into_bytes()),
+                        };
+                        match response {
+                            Ok(response) => responder.respond(response),
+                            Err(error) => tracing::error!(page = %page_name, %error, "Failed to build page response"),
+                        }
+                    });
+                })
+                .build()
+        }
     }
 }
\ No newline at end of file
@@ -0,0 +1,283 @@
+// taurifest/src/runtime/taurifest.js
+//
+// Клиентский runtime Taurifest. Встраивается в страницы из секции `pages`,
+// отдается по адресу `taurifest://localhost/__taurifest/runtime.js` и может
+// быть внедрен в каждое окно через `Builder::inject_runtime(true)`.
+//
+// Атрибуты:
+//   atom-action="addTodo"   - имя роута для `run_action` (устаревший вариант: `data-action`).
+//                              На `<form>` срабатывает по submit, на остальных элементах - по click.
+//   atom-on="change"        - другое DOM-событие для запуска действия.
+//   atom-target="#list"     - куда вставить HTML, если роут обновляет один компонент.
+//   atom-body='{"id": 1}'   - дополнительные поля `body` (JSON).
+//   atom-component="name"   - контейнер компонента (его расставляет движок на страницах).
+//   atom-reads="todos"      - коннекторы для перерисовки контейнера после hot reload
+//                              (через запятую; нужно только на собственных страницах).
+(function () {
+  'use strict';
+
+  if (window.taurifest) return; // Защита от двойного подключения (страница + init-скрипт).
+
+  var ACTION_SELECTOR = '[atom-action], [data-action]';
+
+  function invoke(cmd, args) {
+    var tauri = window.__TAURI_INTERNALS__ || (window.__TAURI__ && window.__TAURI__.core);
+    return tauri.invoke(cmd, args);
+  }
+
+  function actionName(element) {
+    return element.getAttribute('atom-action') || element.getAttribute('data-action');
+  }
+
+  function componentContainer(name) {
+    return document.querySelector('[atom-component="' + name + '"]');
+  }
+
+  // --- Сбор `body` ---
+
+  function collectForm(form) {
+    var body = {};
+    Array.prototype.forEach.call(form.elements, function (field) {
+      if (!field.name || field.disabled) return;
+      var value;
+      if (field.type === 'checkbox') {
+        // Флажок без явного `value` превращается в boolean.
+        if (!field.hasAttribute('value')) {
+          body[field.name] = field.checked;
+          return;
+        }
+        if (!field.checked) return;
+        value = field.value;
+      } else if (field.type === 'radio') {
+        if (!field.checked) return;
+        value = field.value;
+      } else if (field.type === 'number' || field.type === 'range') {
+        value = field.value === '' ? null : Number(field.value);
+      } else if (field.type === 'select-multiple') {
+        body[field.name] = Array.prototype.filter.call(field.options, function (o) { return o.selected; })
+          .map(function (o) { return o.value; });
+        return;
+      } else if (field.type === 'submit' || field.type === 'button' || field.type === 'file') {
+        return;
+      } else {
+        value = field.value;
+      }
+      // Несколько полей с одним именем собираются в массив.
+      if (Object.prototype.hasOwnProperty.call(body, field.name)) {
+        body[field.name] = [].concat(body[field.name], value);
+      } else {
+        body[field.name] = value;
+      }
+    });
+    return body;
+  }
+
+  function collectBody(element) {
+    var body = {};
+    var form = element.tagName === 'FORM' ? element : element.form || element.closest('form');
+    if (form) Object.assign(body, collectForm(form));
+    if (element.tagName !== 'FORM' && element.name && element.value !== undefined) {
+      body[element.name] = element.value;
+    }
+    var extra = element.getAttribute('atom-body');
+    if (extra) {
+      try {
+        Object.assign(body, JSON.parse(extra));
+      } catch (error) {
+        console.error('[taurifest] Invalid JSON in atom-body:', extra);
+      }
+    }
+    return body;
+  }
+
+  // --- Применение ответа `run_action` ---
+
+  function findKeyed(container, key) {
+    var escaped = window.CSS && CSS.escape ? CSS.escape(key) : key;
+    return container.querySelector('[key="' + key.replace(/"/g, '\\"') + '"]') ||
+      container.querySelector('#' + escaped);
+  }
+
+  // Применяет патчи из `html_diff`. Бросает исключение, если DOM не совпадает
+  // с ожидаемым - тогда вызывающий код откатывается на полную замену HTML.
+  function applyPatches(container, patches) {
+    patches.forEach(function (patch) {
+      var element;
+      switch (patch.op) {
+        case 'replace':
+          element = findKeyed(container, patch.key);
+          if (!element) throw new Error('missing key ' + patch.key);
+          element.outerHTML = patch.html;
+          break;
+        case 'remove':
+          element = findKeyed(container, patch.key);
+          if (element) element.remove();
+          break;
+        case 'insert':
+          element = findKeyed(container, patch.after || patch.before);
+          if (!element) throw new Error('missing key ' + (patch.after || patch.before));
+          element.insertAdjacentHTML(patch.after ? 'afterend' : 'beforebegin', patch.html);
+          break;
+        default:
+          throw new Error('unknown patch op ' + patch.op);
+      }
+    });
+  }
+
+  function update(container, html, patches) {
+    if (!container) return;
+    if (patches) {
+      try {
+        applyPatches(container, patches);
+        return;
+      } catch (error) {
+        // Не удалось - используем полный HTML ниже.
+      }
+    }
+    container.innerHTML = html;
+  }
+
+  // Вставляет HTML из ответа: строку для одного компонента (`component`)
+  // или объект `{ имя: html }` с необязательными селекторами в `targets`.
+  function applyResponse(response, fallbackTarget) {
+    if (!response || response.html === undefined) return response;
+
+    if (typeof response.html === 'string') {
+      var container = fallbackTarget || (response.component && componentContainer(response.component));
+      update(container, response.html, response.patches);
+      return response;
+    }
+
+    var targets = response.targets || {};
+    var patches = response.patches || {};
+    Object.keys(response.html).forEach(function (name) {
+      var target = targets[name] ? document.querySelector(targets[name]) : componentContainer(name);
+      update(target, response.html[name], patches[name]);
+    });
+    return response;
+  }
+
+  // Подписывает страницу на событие сокета с `render`: пришедший
+  // `{ component, html }` вставляется так же, как ответ `run_action`.
+  // С `callback` вместо этого вызывается он (например, для событий шага `emit`).
+  // Слушатель привязан к текущему окну, чтобы работали адресаты `others`/`origin`.
+  function listen(event, callback) {
+    var internals = window.__TAURI_INTERNALS__;
+    var handler = internals.transformCallback(function (message) {
+      (callback || applyResponse)(message.payload);
+    });
+    var target = { kind: 'WebviewWindow', label: internals.metadata.currentWebview.label };
+    return invoke('plugin:event|listen', { event: event, target: target, handler: handler });
+  }
+
+  // --- Hot reload ---
+
+  // Страница из секции `pages`, которую отдает сам движок.
+  function isEnginePage() {
+    return location.protocol === 'taurifest:' || location.hostname === 'taurifest.localhost';
+  }
+
+  // После `taurifest://reload` страницы движка перезагружаются целиком: могли
+  // измениться состав компонентов, layout и стили. На собственных страницах
+  // заново отрисовываются только контейнеры `[atom-component]`.
+  function reload() {
+    if (isEnginePage()) {
+      location.reload();
+      return;
+    }
+    document.querySelectorAll('[atom-component]').forEach(function (container) {
+      var reads = (container.getAttribute('atom-reads') || '').split(',')
+        .map(function (name) { return name.trim(); })
+        .filter(Boolean);
+      invoke('render_component', { component: container.getAttribute('atom-component'), reads: reads })
+        .then(function (response) { container.innerHTML = response.html; })
+        .catch(function (error) {
+          console.error('[taurifest] Failed to re-render "' + container.getAttribute('atom-component') + '":', error);
+        });
+    });
+  }
+
+  // --- Запуск действий ---
+
+  function setLoading(elements, loading) {
+    elements.forEach(function (element) {
+      if (!element) return;
+      if (loading) {
+        element.setAttribute('atom-loading', '');
+        element.setAttribute('aria-busy', 'true');
+      } else {
+        element.removeAttribute('atom-loading');
+        element.removeAttribute('aria-busy');
+      }
+    });
+  }
+
+  function runAction(name, body, options) {
+    options = options || {};
+    var target = options.target || null;
+    var busy = [options.source, target];
+    var controls = options.source && options.source.tagName === 'FORM'
+      ? Array.prototype.filter.call(options.source.elements, function (e) { return !e.disabled; })
+      : [];
+
+    setLoading(busy, true);
+    controls.forEach(function (control) { control.disabled = true; });
+
+    return invoke('run_action', { name: name, body: body || {}, patch: true })
+      .then(function (response) { return applyResponse(response, target); })
+      .catch(function (error) {
+        console.error('[taurifest] Action "' + name + '" failed:', error);
+        throw error;
+      })
+      .finally(function () {
+        setLoading(busy, false);
+        controls.forEach(function (control) { control.disabled = false; });
+      });
+  }
+
+  function trigger(element) {
+    if (element.hasAttribute('atom-loading')) return; // Действие уже выполняется.
+    var targetSelector = element.getAttribute('atom-target');
+    runAction(actionName(element), collectBody(element), {
+      source: element,
+      target: targetSelector ? document.querySelector(targetSelector) : null,
+    }).catch(function () {});
+  }
+
+  function defaultEvent(element) {
+    return element.tagName === 'FORM' ? 'submit' : 'click';
+  }
+
+  function handle(event) {
+    var element = event.target.closest && event.target.closest(ACTION_SELECTOR);
+    // Для submit/click ищем ближайший элемент с действием, для остальных событий -
+    // только тот, у которого это событие указано в `atom-on`.
+    while (element) {
+      var expected = element.getAttribute('atom-on') || defaultEvent(element);
+      if (expected === event.type) break;
+      element = element.parentElement && element.parentElement.closest(ACTION_SELECTOR);
+    }
+    if (!element) return;
+    event.preventDefault();
+    trigger(element);
+  }
+
+  ['submit', 'click', 'change', 'input'].forEach(function (type) {
+    document.addEventListener(type, handle);
+  });
+
+  if (window.__TAURI_INTERNALS__) {
+    listen('taurifest://reload', reload).catch(function (error) {
+      console.error('[taurifest] Failed to subscribe to reload:', error);
+    });
+  }
+
+  window.taurifest = {
+    invoke: invoke,
+    runAction: runAction,
+    applyResponse: applyResponse,
+    collectForm: collectForm,
+    listen: listen,
+    reload: reload,
+  };
+})();
@@ -0,0 +1,305 @@
+// taurifest/src/testing.rs
+
+use std::collections::HashMap;
+use std::fmt;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::core::errors::AppError;
+use crate::Engine;
+
+/// Папка с тестами роутов внутри папки приложения.
+pub const TESTS_DIR: &str = "tests";
+
+/// Профиль манифеста, с которым запускаются тесты, если он объявлен.
+pub const TEST_PROFILE: &str = "test";
+
+/// Один тест роута из файла `tests/*.json`. Файл содержит либо один такой
+/// объект, либо массив объектов.
+///
+/// ```json
+/// {
+///   "name": "adds a todo",
+///   "given": { "todos": { "items": [] } },
+///   "route": "addTodo",
+///   "body": { "text": "Milk" },
+///   "expect": {
+///     "data": { "todos": { "items": [{ "text": "Milk", "done": false }] } },
+///     "context": { "added": true },
+///     "html": { "todoList": ["<li>Milk</li>"] }
+///   }
+/// }
+/// ```
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct TestCase {
+    /// Название теста. По умолчанию - имя файла (и номер теста в нем).
+    pub name: Option<String>,
+    /// Состояние коннекторов перед запуском роута. Коннекторы, которых здесь
+    /// нет, начинают с `initial_state` из манифеста.
+    #[serde(default)]
+    pub given: HashMap<String, Value>,
+    pub route: String,
+    #[serde(default)]
+    pub body: Value,
+    #[serde(default)]
+    pub expect: Expectations,
+}
+
+/// Что должно получиться после выполнения роута.
+///
+/// Объекты сравниваются только по указанным ключам (лишние ключи в фактическом
+/// значении допускаются), массивы - по длине и поэлементно, остальное - на равенство.
+#[derive(Debug, Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Expectations {
+    /// Состояние коннекторов после выполнения (то, что в них записано).
+    #[serde(default)]
+    pub data: HashMap<String, Value>,
+    /// Итоговый временный `context` роута.
+    pub context: Option<Value>,
+    /// Фрагменты HTML, которые должны встретиться в отрисованных компонентах из `update`.
+    /// Пробельные символы при сравнении схлопываются.
+    #[serde(default)]
+    pub html: HashMap<String, Vec<String>>,
+}
+
+/// Результат одного теста: пустой список `failures` означает успех.
+#[derive(Debug, Clone)]
+pub struct CaseResult {
+    pub file: PathBuf,
+    pub name: String,
+    pub failures: Vec<String>,
+}
+
+impl CaseResult {
+    pub fn passed(&self) -> bool {
+        self.failures.is_empty()
+    }
+}
+
+/// Результаты всех тестов из папки `tests`.
+#[derive(Debug, Clone, Default)]
+pub struct SuiteReport {
+    pub cases: Vec<CaseResult>,
+}
+
+impl SuiteReport {
+    pub fn passed(&self) -> bool {
+        self.cases.iter().all(CaseResult::passed)
+    }
+
+    /// Паникует с читаемым отчетом, если хотя бы один тест не прошел.
+    /// Удобно вызывать из `#[test]`.
+    pub fn assert_passed(&self) {
+        if !self.passed() {
+            panic!("Route tests failed:\n{}", self);
+        }
+    }
+}
+
+impl fmt::Display for SuiteReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for case in &self.cases {
+            let status = if case.passed() { "ok  " } else { "FAIL" };
+            writeln!(f, "{} {} ({})", status, case.name, case.file.display())?;
+            for failure in &case.failures {
+                for (index, line) in failure.lines().enumerate() {
+                    let bullet = if index == 0 { "-" } else { " " };
+                    writeln!(f, "       {} {}", bullet, line)?;
+                }
+            }
+        }
+        let failed = self.cases.iter().filter(|case| !case.passed()).count();
+        write!(f, "{} passed, {} failed", self.cases.len() - failed, failed)
+    }
+}
+
+/// Запускает все тесты из `app_path/tests/*.json` без окон и без файлов данных:
+/// каждый тест получает свежий движок с коннекторами в памяти. Если в манифесте
+/// есть профиль `test`, тесты идут с ним (`TAURIFEST_PROFILE` по-прежнему важнее).
+///
+/// Ошибка возвращается, только если тесты не удалось найти или загрузить
+/// приложение; непрошедшие тесты отражаются в отчете.
+///
+/// Блокирует текущий поток, поэтому вызывается только из обычного `#[test]`.
+/// Внутри асинхронного рантайма (`#[tokio::test]`) `block_on` паникует -
+/// там нужен [`run_suite_async`].
+///
+/// ```ignore
+/// #[test]
+/// fn routes() {
+///     taurifest::testing::run_suite("app").unwrap().assert_passed();
+/// }
+/// ```
+pub fn run_suite(app_path: impl AsRef<Path>) -> Result<SuiteReport, AppError> {
+    tauri::async_runtime::block_on(run_suite_async(app_path.as_ref()))
+}
+
+/// То же, что [`run_suite`], для асинхронного кода.
+///
+/// ```ignore
+/// #[tokio::test]
+/// async fn routes() {
+///     taurifest::testing::run_suite_async("app").await.unwrap().assert_passed();
+/// }
+/// ```
+pub async fn run_suite_async(app_path: impl AsRef<Path>) -> Result<SuiteReport, AppError> {
+    let app_path = app_path.as_ref();
+    let tests_path = app_path.join(TESTS_DIR);
+
+    let mut files: Vec<PathBuf> = std::fs::read_dir(&tests_path)
+        .map_err(|e| AppError::Config(format!("Failed to read tests directory {:?}: {}", tests_path, e)))?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    files.sort();
+
+    // Приложение загружаем один раз заранее, чтобы ошибка в манифесте
+    // была одной ошибкой, а не провалом каждого теста. Профиль из
+    // `TAURIFEST_PROFILE` этот движок уже выбрал сам; иначе берем `test`, если он есть.
+    let engine = Engine::load_in_memory(app_path, None)?;
+    let profile = match engine.profile() {
+        Some(profile) => Some(profile.to_string()),
+        None => engine.manifest().profiles.contains_key(TEST_PROFILE).then(|| TEST_PROFILE.to_string()),
+    };
+
+    let mut report = SuiteReport::default();
+    for file in files {
+        let relative = file.strip_prefix(app_path).unwrap_or(&file).to_path_buf();
+        match load_cases(&file) {
+            Ok(cases) => {
+                let numbered = cases.len() > 1;
+                for (index, case) in cases.into_iter().enumerate() {
+                    let name = case.name.clone().unwrap_or_else(|| default_name(&file, numbered.then_some(index)));
+                    let failures = run_case(app_path, profile.as_deref(), &case).await;
+                    report.cases.push(CaseResult { file: relative.clone(), name, failures });
+                }
+            }
+            Err(error) => report.cases.push(CaseResult {
+                file: relative,
+                name: default_name(&file, None),
+                failures: vec![error],
+            }),
+        }
+    }
+
+    Ok(report)
+}
+
+/// Читает тесты из файла: один объект или массив объектов.
+fn load_cases(file: &Path) -> Result<Vec<TestCase>, String> {
+    let content = std::fs::read_to_string(file).map_err(|e| format!("Failed to read test file: {}", e))?;
+    let value: Value = serde_json::from_str(&content).map_err(|e| format!("Invalid JSON: {}", e))?;
+    let items = match value {
+        Value::Array(items) => items,
+        single => vec![single],
+    };
+    items.into_iter()
+        .enumerate()
+        .map(|(index, item)| serde_json::from_value(item).map_err(|e| format!("Invalid test #{}: {}", index + 1, e)))
+        .collect()
+}
+
+fn default_name(file: &Path, index: Option<usize>) -> String {
+    let stem = file.file_stem().map(|stem| stem.to_string_lossy().into_owned()).unwrap_or_default();
+    match index {
+        Some(index) => format!("{} #{}", stem, index + 1),
+        None => stem,
+    }
+}
+
+/// Выполняет один тест и возвращает список расхождений.
+async fn run_case(app_path: &Path, profile: Option<&str>, case: &TestCase) -> Vec<String> {
+    let engine = match Engine::load_in_memory(app_path, profile) {
+        Ok(engine) => engine,
+        Err(e) => return vec![format!("Failed to load app: {}", e)],
+    };
+
+    let mut failures = Vec::new();
+
+    for (connector_name, state) in &case.given {
+        match engine.connector_manager.get(connector_name) {
+            Some(connector) => {
+                if let Err(e) = connector.write(state).await {
+                    failures.push(format!("given.{}: failed to write initial state: {}", connector_name, e));
+                }
+            }
+            None => failures.push(format!("given.{}: connector is not defined in manifest", connector_name)),
+        }
+    }
+    if !failures.is_empty() {
+        return failures;
+    }
+
+    let result = match engine.run_route(&case.route, case.body.clone()).await {
+        Ok(result) => result,
+        Err(e) => return vec![format!("Route '{}' failed: {}", case.route, e)],
+    };
+
+    for (connector_name, expected) in &case.expect.data {
+        let path = format!("data.{}", connector_name);
+        match engine.connector_manager.get(connector_name) {
+            Some(connector) => match connector.read().await {
+                Ok(actual) => compare(&path, expected, &actual, &mut failures),
+                Err(e) => failures.push(format!("{}: failed to read connector: {}", path, e)),
+            },
+            None => failures.push(format!("{}: connector is not defined in manifest", path)),
+        }
+    }
+
+    if let Some(expected) = &case.expect.context {
+        compare("context", expected, &result.context, &mut failures);
+    }
+
+    for (component, snippets) in &case.expect.html {
+        let Some(rendered) = result.rendered.iter().find(|rendered| &rendered.component == component) else {
+            failures.push(format!("html.{}: component was not rendered by route '{}'", component, case.route));
+            continue;
+        };
+        let html = collapse_whitespace(&rendered.html);
+        for snippet in snippets {
+            if !html.contains(&collapse_whitespace(snippet)) {
+                failures.push(format!("html.{}: snippet not found\n  expected: {}\n  rendered: {}", component, snippet, html));
+            }
+        }
+    }
+
+    failures
+}
+
+/// Сравнивает ожидаемое значение с фактическим и записывает расхождения
+/// в виде `путь: expected ..., got ...`.
+fn compare(path: &str, expected: &Value, actual: &Value, failures: &mut Vec<String>) {
+    match (expected, actual) {
+        (Value::Object(expected_map), Value::Object(actual_map)) => {
+            for (key, expected_value) in expected_map {
+                let child_path = format!("{}.{}", path, key);
+                match actual_map.get(key) {
+                    Some(actual_value) => compare(&child_path, expected_value, actual_value, failures),
+                    None => failures.push(format!("{}: expected {}, but the key is missing", child_path, expected_value)),
+                }
+            }
+        }
+        (Value::Array(expected_items), Value::Array(actual_items)) => {
+            if expected_items.len() != actual_items.len() {
+                failures.push(format!(
+                    "{}: expected {} items, got {}\n  expected: {}\n  actual:   {}",
+                    path, expected_items.len(), actual_items.len(), expected, actual
+                ));
+                return;
+            }
+            for (index, (expected_item, actual_item)) in expected_items.iter().zip(actual_items).enumerate() {
+                compare(&format!("{}[{}]", path, index), expected_item, actual_item, failures);
+            }
+        }
+        _ if expected != actual => failures.push(format!("{}: expected {}, got {}", path, expected, actual)),
+        _ => {}
+    }
+}
+
+fn collapse_whitespace(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}